@@ -0,0 +1,56 @@
+use zellij_tile::prelude::TabInfo;
+
+use crate::{i18n::tr, sanitize, truncate};
+
+// NOTE: plain labeled output for screen readers and braille displays - no colors, no glyphs
+pub struct Plain<'a> {
+    pub mode: String,
+    pub session: &'a str,
+    pub tabs: &'a [TabInfo],
+    pub custom: Vec<(&'a str, &'a str)>,
+    pub clock: String,
+}
+
+impl Plain<'_> {
+    pub fn render(&self, cols: usize) -> String {
+        let tabs: Vec<String> = self
+            .tabs
+            .iter()
+            .map(|tab| {
+                let active = if tab.active { "*" } else { "" };
+                format!(
+                    "{} {}{active}",
+                    tab.position + 1,
+                    strip_glyphs(&sanitize::text(&tab.name))
+                )
+            })
+            .collect();
+
+        let mut parts = vec![
+            format!(
+                "{} {}",
+                tr("heading_mode"),
+                strip_glyphs(&self.mode.to_lowercase())
+            ),
+            format!("{} {}", tr("heading_session"), strip_glyphs(self.session)),
+            format!("{}: {}", tr("heading_tabs"), tabs.join(", ")),
+        ];
+        parts.extend(
+            self.custom
+                .iter()
+                .map(|(name, text)| format!("{} {}", name.to_uppercase(), strip_glyphs(text))),
+        );
+        parts.push(strip_glyphs(&self.clock).trim().to_string());
+
+        let line = parts.join(" | ");
+        let (line, width) = truncate(&line, cols);
+        format!("{line}{}", " ".repeat(cols.saturating_sub(width)))
+    }
+}
+
+// INFO: drop nerd font / powerline icons which live in the unicode private use areas
+pub fn strip_glyphs(text: &str) -> String {
+    text.chars()
+        .filter(|c| !matches!(*c as u32, 0xE000..=0xF8FF | 0xF0000..=0xFFFFD | 0x100000..=0x10FFFD))
+        .collect()
+}
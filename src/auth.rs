@@ -0,0 +1,56 @@
+use anstyle::Style as AnsiStyle;
+use zellij_tile::prelude::*;
+
+use crate::{glyph, poll, widget::Widget, Palette, Segment};
+
+pub const DEFAULT_COMMAND: &str =
+    "journalctl -u sshd --since -5min 2>/dev/null | grep -c 'Failed password'";
+
+// NOTE: recent failed logins counted by the `auth_check` command, hidden while there are none
+#[derive(Default)]
+pub struct Auth {
+    count: usize,
+    loaded: bool,
+}
+
+impl Widget for Auth {
+    fn interested_events(&self) -> Vec<EventType> {
+        vec![EventType::RunCommandResult]
+    }
+
+    // INFO: returns `true` when the count changed
+    fn update(&mut self, event: &Event) -> bool {
+        let Event::RunCommandResult(_, stdout, _, context) = event else {
+            return false;
+        };
+        if context.get(poll::POLL).map(String::as_str) != Some("auth") {
+            return false;
+        }
+        let count = String::from_utf8_lossy(stdout)
+            .trim()
+            .parse()
+            .unwrap_or_default();
+        let changed = self.count != count || !self.loaded;
+        self.count = count;
+        self.loaded = true;
+        changed
+    }
+
+    fn is_loaded(&self) -> bool {
+        self.loaded
+    }
+
+    fn render(&self, palette: &Palette) -> Segment {
+        if self.count == 0 {
+            return Segment::empty();
+        }
+        Segment::new(
+            Box::new(format!("{} {}", glyph("󰒃", "auth"), self.count)),
+            AnsiStyle::new()
+                .fg_color(palette.black)
+                .bg_color(palette.red)
+                .bold(),
+            palette,
+        )
+    }
+}
@@ -0,0 +1,92 @@
+use anstyle::Style as AnsiStyle;
+use zellij_tile::prelude::*;
+
+use crate::{glyph, i18n, poll, widget::Widget, Palette, Segment};
+
+// NOTE: plugins can't read `/sys` directly - prints capacity, status, and the remaining
+// energy with its drain rate (or charge with current, depending on the driver)
+pub const DEFAULT_COMMAND: &str =
+    "cd /sys/class/power_supply/BAT* 2>/dev/null && cat capacity status \
+     && (cat energy_now power_now 2>/dev/null || cat charge_now current_now 2>/dev/null)";
+
+#[derive(Default)]
+pub struct Battery {
+    capacity: Option<u8>,
+    charging: bool,
+    minutes: Option<u64>,
+    loaded: bool,
+    pub critical: u8,
+}
+
+impl Widget for Battery {
+    fn interested_events(&self) -> Vec<EventType> {
+        vec![EventType::RunCommandResult]
+    }
+
+    // INFO: returns `true` when anything shown changed
+    fn update(&mut self, event: &Event) -> bool {
+        let Event::RunCommandResult(_, stdout, _, context) = event else {
+            return false;
+        };
+        if context.get(poll::POLL).map(String::as_str) != Some("battery") {
+            return false;
+        }
+        let stdout = String::from_utf8_lossy(stdout);
+        let mut lines = stdout.lines().map(str::trim);
+        let capacity = lines.next().and_then(|x| x.parse().ok());
+        let charging = lines.next().is_some_and(|x| x == "Charging" || x == "Full");
+        let remaining: Option<f64> = lines.next().and_then(|x| x.parse().ok());
+        let rate: Option<f64> = lines.next().and_then(|x| x.parse().ok());
+        let minutes = match (remaining, rate) {
+            (Some(remaining), Some(rate)) if !charging && rate > 0.0 => {
+                Some((remaining / rate * 60.0) as u64)
+            }
+            _ => None,
+        };
+
+        let changed = (capacity, charging, minutes) != (self.capacity, self.charging, self.minutes)
+            || !self.loaded;
+        self.capacity = capacity;
+        self.charging = charging;
+        self.minutes = minutes;
+        self.loaded = true;
+        changed
+    }
+
+    fn is_loaded(&self) -> bool {
+        self.loaded
+    }
+
+    fn render(&self, palette: &Palette) -> Segment {
+        let Some(capacity) = self.capacity else {
+            return Segment::empty();
+        };
+        let (icon, bg) = match capacity {
+            _ if self.charging => (glyph("󰂄", "chg"), palette.green),
+            x if x <= self.critical => (glyph("󰂃", "bat!"), palette.red),
+            x if x <= 30 => (glyph("󰁻", "bat"), palette.yellow),
+            _ => (glyph("󰁹", "bat"), palette.green),
+        };
+        Segment::new(
+            Box::new(format!("{icon} {capacity}%")),
+            AnsiStyle::new().fg_color(palette.black).bg_color(bg),
+            palette,
+        )
+    }
+}
+
+impl Battery {
+    pub fn is_critical(&self) -> bool {
+        !self.charging && self.capacity.is_some_and(|x| x <= self.critical)
+    }
+
+    // INFO: text of the takeover shown while the battery is critical
+    pub fn warning(&self) -> String {
+        let mut warning = i18n::tr("battery_critical")
+            .replace("{percent}", &self.capacity.unwrap_or_default().to_string());
+        if let Some(minutes) = self.minutes {
+            warning.push_str(&format!(" · ~{minutes} min"));
+        }
+        warning
+    }
+}
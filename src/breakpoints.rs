@@ -0,0 +1,55 @@
+use std::collections::BTreeMap;
+
+// NOTE: prefix of configuration keys like `hide_below_80 = "clock,session"`
+const HIDE_BELOW_PREFIX: &str = "hide_below_";
+
+#[derive(Default)]
+pub struct Breakpoints {
+    rules: Vec<(usize, Vec<String>)>,
+}
+
+impl Breakpoints {
+    pub fn new(config: &BTreeMap<String, String>) -> Self {
+        let rules = config
+            .iter()
+            .filter_map(|(key, value)| {
+                let cols = key.strip_prefix(HIDE_BELOW_PREFIX)?.parse().ok()?;
+                let names = value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|x| !x.is_empty())
+                    .map(String::from)
+                    .collect();
+                Some((cols, names))
+            })
+            .collect();
+
+        Self { rules }
+    }
+
+    pub fn is_hidden(&self, name: &str, cols: usize) -> bool {
+        self.rules
+            .iter()
+            .any(|(below, names)| cols < *below && names.iter().any(|x| x == name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hides_below_the_narrowest_matching_width() {
+        let config = BTreeMap::from([
+            ("hide_below_80".to_string(), "clock, session".to_string()),
+            ("hide_below_120".to_string(), "session".to_string()),
+            ("hide_below_wide".to_string(), "tabs".to_string()),
+        ]);
+        let breakpoints = Breakpoints::new(&config);
+        assert!(breakpoints.is_hidden("clock", 79));
+        assert!(!breakpoints.is_hidden("clock", 80));
+        assert!(breakpoints.is_hidden("session", 100));
+        assert!(!breakpoints.is_hidden("session", 120));
+        assert!(!breakpoints.is_hidden("tabs", 10));
+    }
+}
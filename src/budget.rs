@@ -0,0 +1,177 @@
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::{grapheme_width, DisplayExt};
+
+const RESET: &str = "\x1b[0m";
+
+// NOTE: final safety net under the layout - never emits more than the columns it was given,
+// even when a segment reports a wrong width, so the bar can't wrap
+pub struct Budget {
+    left: usize,
+    // NOTE: cells still to be scrolled past before anything is emitted
+    skip: usize,
+    line: String,
+}
+
+impl Budget {
+    pub fn new(cols: usize) -> Self {
+        Self {
+            left: cols,
+            skip: 0,
+            line: String::new(),
+        }
+    }
+
+    pub fn skip(mut self, cells: usize) -> Self {
+        self.skip = cells;
+        self
+    }
+
+    pub fn emit(&mut self, text: &str) {
+        if self.left == 0 {
+            return;
+        }
+        let skipped;
+        let text = if self.skip > 0 {
+            let (rest, cells) = skip(text, self.skip);
+            self.skip -= cells;
+            skipped = rest;
+            skipped.as_str()
+        } else {
+            text
+        };
+        let width = text.display_len();
+        if width <= self.left {
+            self.left -= width;
+            self.line.push_str(text);
+        } else {
+            self.line.push_str(&clip(text, self.left));
+            self.line.push_str(RESET);
+            self.left = 0;
+        }
+    }
+
+    pub fn finish(self) -> String {
+        self.line
+    }
+}
+
+// INFO: `text` cut to `cols` cells in one go, for lines not laid out through a budget
+pub fn limit(text: &str, cols: usize) -> String {
+    let mut budget = Budget::new(cols);
+    budget.emit(text);
+    budget.finish()
+}
+
+// INFO: drops up to `width` cells, escape sequences are kept so styles still apply, a wide
+// cluster cut in half leaves a space
+fn skip(text: &str, width: usize) -> (String, usize) {
+    let mut kept = String::new();
+    let mut count = 0;
+    let mut rest = text;
+    while !rest.is_empty() {
+        if rest.starts_with('\x1b') {
+            let end = escape_len(rest);
+            kept.push_str(&rest[..end]);
+            rest = &rest[end..];
+            continue;
+        }
+        let run = rest.find('\x1b').unwrap_or(rest.len());
+        for grapheme in rest[..run].graphemes(true) {
+            if count < width {
+                count += grapheme_width(grapheme);
+                if count > width {
+                    kept.push(' ');
+                    count = width;
+                }
+            } else {
+                kept.push_str(grapheme);
+            }
+        }
+        rest = &rest[run..];
+    }
+    (kept, count)
+}
+
+// INFO: keeps the whole clusters fitting `width` cells along with every escape sequence in between
+fn clip(text: &str, width: usize) -> String {
+    let mut clipped = String::new();
+    let mut count = 0;
+    let mut rest = text;
+    while !rest.is_empty() {
+        if rest.starts_with('\x1b') {
+            let end = escape_len(rest);
+            clipped.push_str(&rest[..end]);
+            rest = &rest[end..];
+            continue;
+        }
+        let run = rest.find('\x1b').unwrap_or(rest.len());
+        for grapheme in rest[..run].graphemes(true) {
+            count += grapheme_width(grapheme);
+            if count > width {
+                return clipped;
+            }
+            clipped.push_str(grapheme);
+        }
+        rest = &rest[run..];
+    }
+    clipped
+}
+
+// INFO: CSI sequences end with a final byte, OSC ones (hyperlinks) with BEL or ST
+pub fn escape_len(text: &str) -> usize {
+    let bytes = text.as_bytes();
+    match bytes.get(1) {
+        Some(b'[') => bytes[2..]
+            .iter()
+            .position(|x| (0x40..=0x7e).contains(x))
+            .map_or(bytes.len(), |x| x + 3),
+        Some(b']') => {
+            let bel = text.find('\x07').map(|x| x + 1);
+            let st = text.find("\x1b\\").map(|x| x + 2);
+            bel.into_iter().chain(st).min().unwrap_or(bytes.len())
+        }
+        Some(_) => 1 + text[1..].chars().next().map_or(0, char::len_utf8),
+        None => 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limits_overflowing_lines() {
+        let line = format!("\x1b[44m{}", "x".repeat(12));
+        assert_eq!(limit(&line, 8).display_len(), 8);
+        assert_eq!(limit("short", 8), "short");
+    }
+
+    #[test]
+    fn clips_whole_clusters_and_keeps_escapes() {
+        let flag = "\u{1f1f5}\u{1f1f1}";
+        let mut budget = Budget::new(4);
+        budget.emit(&format!("\x1b[31me\u{301}{flag}\x1b[0m漢字"));
+        assert_eq!(
+            budget.finish(),
+            format!("\x1b[31me\u{301}{flag}\x1b[0m{RESET}")
+        );
+    }
+
+    #[test]
+    fn skips_scrolled_cells() {
+        let mut budget = Budget::new(3).skip(4);
+        budget.emit("ab");
+        budget.emit("\x1b[31mc漢de");
+        assert_eq!(budget.finish(), "\x1b[31m de");
+    }
+
+    #[test]
+    fn never_exceeds_the_columns() {
+        let mut budget = Budget::new(3);
+        budget.emit("ab");
+        budget.emit("cde");
+        budget.emit("f");
+        assert_eq!(strip_ansi_escapes::strip_str(budget.finish()), "abc");
+    }
+}
@@ -0,0 +1,151 @@
+use std::collections::BTreeMap;
+
+use anstyle::Style as AnsiStyle;
+use zellij_tile::prelude::*;
+
+use crate::{widget::Widget, DisplayExt, Palette, Segment};
+
+// NOTE: prefix of configuration keys like `button_1 = "icon=;action=ToggleFloatingPanes"`
+const PREFIX: &str = "button_";
+
+struct Button {
+    icon: String,
+    action: String,
+}
+
+impl Button {
+    fn segment(&self, palette: &Palette) -> Segment {
+        Segment::new(
+            Box::new(self.icon.clone()),
+            AnsiStyle::new()
+                .fg_color(palette.black)
+                .bg_color(palette.gray),
+            palette,
+        )
+    }
+
+    // INFO: colors don't take any cells, so any palette measures the same
+    fn width(&self) -> usize {
+        self.segment(&Palette::default()).display_len()
+    }
+}
+
+// NOTE: mini toolbar of zellij actions, ordered by the key suffix
+#[derive(Default)]
+pub struct Buttons {
+    buttons: Vec<Button>,
+    // NOTE: button highlighted by keyboard navigation
+    pub selected: Option<usize>,
+}
+
+impl Widget for Buttons {
+    fn render(&self, palette: &Palette) -> Segment {
+        let buttons: String = self
+            .buttons
+            .iter()
+            .enumerate()
+            .map(|(index, button)| match self.selected == Some(index) {
+                true => button.segment(palette).highlighted().to_string(),
+                false => button.segment(palette).to_string(),
+            })
+            .collect();
+        Segment::raw(Box::new(buttons))
+    }
+}
+
+impl Buttons {
+    pub fn new(config: &BTreeMap<String, String>) -> Self {
+        let buttons = config
+            .iter()
+            .filter(|(key, _)| key.starts_with(PREFIX))
+            .filter_map(|(_, value)| {
+                let fields: BTreeMap<&str, &str> = value
+                    .split(';')
+                    .filter_map(|x| x.split_once('='))
+                    .map(|(key, value)| (key.trim(), value.trim()))
+                    .collect();
+                let action = fields.get("action")?;
+                Some(Button {
+                    icon: fields.get("icon").unwrap_or(action).to_string(),
+                    action: action.to_string(),
+                })
+            })
+            .collect();
+        Self {
+            buttons,
+            selected: None,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buttons.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.buttons.len()
+    }
+
+    // INFO: columns from the start of the element to the button at `index`
+    pub fn offset(&self, index: usize) -> usize {
+        self.buttons.iter().take(index).map(Button::width).sum()
+    }
+
+    // INFO: action of the button at `offset` columns from the start of the element
+    pub fn action_at(&self, offset: usize) -> Option<&str> {
+        let mut start = 0;
+        for button in &self.buttons {
+            let end = start + button.width();
+            if (start..end).contains(&offset) {
+                return Some(&button.action);
+            }
+            start = end;
+        }
+        None
+    }
+}
+
+// NOTE: actions with a plugin command counterpart are run directly, the rest goes through
+// `zellij action` - `ToggleFloatingPanes` -> `zellij action toggle-floating-panes`
+pub fn run(action: &str, session: &str) {
+    match action {
+        "NewTab" => new_tab(),
+        "CloseTab" => close_focused_tab(),
+        "GoToNextTab" => go_to_next_tab(),
+        "GoToPreviousTab" => go_to_previous_tab(),
+        "ToggleTab" => toggle_tab(),
+        "FocusNextPane" => focus_next_pane(),
+        "FocusPreviousPane" => focus_previous_pane(),
+        "CloseFocus" => close_focus(),
+        "ToggleFocusFullscreen" => toggle_focus_fullscreen(),
+        "TogglePaneFrames" => toggle_pane_frames(),
+        "TogglePaneEmbedOrEject" => toggle_pane_embed_or_eject(),
+        "ToggleActiveSyncTab" => toggle_active_tab_sync(),
+        "NextSwapLayout" => next_swap_layout(),
+        "PreviousSwapLayout" => previous_swap_layout(),
+        "EditScrollback" => edit_scrollback(),
+        "Detach" => detach(),
+        "Quit" => quit_zellij(),
+        action => {
+            let mut words = action.split_whitespace();
+            let Some(name) = words.next() else {
+                return;
+            };
+            let mut command = vec!["zellij", "--session", session, "action"];
+            let name = kebab_case(name);
+            command.push(&name);
+            command.extend(words);
+            run_command(&command, BTreeMap::new());
+        }
+    }
+}
+
+fn kebab_case(name: &str) -> String {
+    let mut kebab = String::new();
+    for (index, c) in name.chars().enumerate() {
+        if c.is_uppercase() && index > 0 {
+            kebab.push('-');
+        }
+        kebab.extend(c.to_lowercase());
+    }
+    kebab
+}
@@ -0,0 +1,119 @@
+use std::cell::Cell;
+
+use crate::segment;
+
+thread_local! {
+    // NOTE: glyphs replacing the outer separators of the first and the last segment of the bar,
+    // `None` keeps the separator
+    static CAPS: Cell<(Option<&'static str>, Option<&'static str>)> = const { Cell::new((None, None)) };
+}
+
+// INFO: `round`, `angled`, `flame` or `square` for both `cap_left` and `cap_right`
+pub fn set_caps(left: Option<&str>, right: Option<&str>) {
+    let left = left.and_then(|x| match x {
+        "round" => Some(""),
+        "angled" => Some(""),
+        "flame" => Some(""),
+        "square" => Some(""),
+        _ => None,
+    });
+    let right = right.and_then(|x| match x {
+        "round" => Some(""),
+        "angled" => Some(""),
+        "flame" => Some(""),
+        "square" => Some(""),
+        _ => None,
+    });
+    CAPS.with(|x| x.set((left, right)));
+}
+
+// NOTE: a rendered segment starts with its separator drawn in inverted colors - swapping them
+// back gives a cap in the segment color
+pub fn left(rendered: String) -> String {
+    let Some(cap) = CAPS.with(Cell::get).0 else {
+        return rendered;
+    };
+    let escapes = leading_escapes(&rendered);
+    let (prefix, rest) = rendered.split_at(escapes);
+    // INFO: mirrored segments already start in their color
+    if let Some(rest) = segment::separator_right().and_then(|(begin, _)| rest.strip_prefix(begin)) {
+        return format!("{prefix}{cap}{rest}");
+    }
+    let rest = rest.strip_prefix(segment::separator().0).unwrap_or(rest);
+    if cap.is_empty() {
+        return rest.to_string();
+    }
+    format!("{}{cap}{rest}", swap_colors(prefix))
+}
+
+// NOTE: the closing separator is already drawn in the segment color, unless mirrored
+pub fn right(rendered: String) -> String {
+    let Some(cap) = CAPS.with(Cell::get).1 else {
+        return rendered;
+    };
+    if let Some(rest) = segment::separator_right().and_then(|(_, end)| rendered.strip_suffix(end)) {
+        let (rest, suffix) = rest.split_at(trailing_escapes(rest));
+        return format!("{rest}{}{cap}", swap_colors(suffix));
+    }
+    let rest = rendered
+        .strip_suffix(segment::separator().1)
+        .unwrap_or(&rendered);
+    format!("{rest}{cap}")
+}
+
+fn leading_escapes(text: &str) -> usize {
+    let mut end = 0;
+    while text[end..].starts_with("\x1b[") {
+        match text[end..].find('m') {
+            Some(x) => end += x + 1,
+            None => break,
+        }
+    }
+    end
+}
+
+// INFO: start of the escape sequences `text` ends with
+fn trailing_escapes(text: &str) -> usize {
+    let mut start = text.len();
+    while text[..start].ends_with('m') {
+        match text[..start].rfind("\x1b[") {
+            Some(x) => start = x,
+            None => break,
+        }
+    }
+    start
+}
+
+// INFO: flips foreground and background parameters of SGR sequences - `30` <-> `40`, `38;5;n` <-> `48;5;n`
+fn swap_colors(escapes: &str) -> String {
+    escapes
+        .split_terminator('m')
+        .map(|x| {
+            let params: Vec<&str> = x.trim_start_matches("\x1b[").split(';').collect();
+            let mut swapped = Vec::new();
+            let mut skip = 0;
+            for (i, param) in params.iter().enumerate() {
+                if skip > 0 {
+                    skip -= 1;
+                    swapped.push(param.to_string());
+                    continue;
+                }
+                let param = match param.parse::<u8>() {
+                    Ok(38) | Ok(48) => {
+                        skip = match params.get(i + 1) {
+                            Some(&"5") => 2,
+                            Some(&"2") => 4,
+                            _ => 0,
+                        };
+                        if *param == "38" { "48" } else { "38" }.to_string()
+                    }
+                    Ok(x @ (30..=37 | 39 | 90..=97)) => (x + 10).to_string(),
+                    Ok(x @ (40..=47 | 49 | 100..=107)) => (x - 10).to_string(),
+                    _ => param.to_string(),
+                };
+                swapped.push(param);
+            }
+            format!("\x1b[{}m", swapped.join(";"))
+        })
+        .collect()
+}
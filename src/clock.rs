@@ -1,16 +1,48 @@
+use anstyle::Style as AnsiStyle;
 use chrono_tz::Tz;
 
 use std::fmt::Display;
 
+use crate::{memo, numerals::Numerals, time, widget::Widget, Palette, Segment};
+
+// NOTE: strftime specifiers showing seconds
+const SECONDS: &[&str] = &["%S", "%s", "%T", "%X", "%c", "%r", "%+"];
+
+pub const DEFAULT_FORMAT: &str = "󰅐 %Y-%m-%dT%H:%M:%S%:z  epoch: %s";
+
 pub struct Clock {
     tz: Tz,
     format: String,
+    numerals: Numerals,
 }
 
 impl Display for Clock {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let now = chrono::Local::now();
-        write!(f, "{}", now.with_timezone(&self.tz).format(&self.format))
+        write!(f, "{}", self.formatted(&self.format))
+    }
+}
+
+// INFO: changes its display on every render without any event
+impl Widget for Clock {
+    fn render(&self, palette: &Palette) -> Segment {
+        Segment::new(
+            Box::new(self.to_string()),
+            AnsiStyle::new()
+                .bg_color(palette.white)
+                .fg_color(palette.black),
+            palette,
+        )
+        .max_width(64)
+        // INFO: seconds go first on narrow terminals
+        .compact(Box::new(self.formatted(&without_seconds(&self.format))))
+        .priority(10)
+    }
+
+    // INFO: a new bucket every second, every minute for formats without seconds
+    fn state(&self) -> Option<u64> {
+        let seconds = SECONDS.iter().any(|x| self.format.contains(x));
+        let now = time::now().timestamp();
+        Some(memo::hash(&if seconds { now } else { now / 60 }))
     }
 }
 
@@ -18,19 +50,77 @@ impl Default for Clock {
     fn default() -> Self {
         Self {
             tz: Tz::UTC,
-            format: "󰅐 %Y-%m-%dT%H:%M:%S%:z  epoch: %s".to_string(),
+            format: DEFAULT_FORMAT.to_string(),
+            numerals: Numerals::default(),
         }
     }
 }
 
 impl Clock {
-    pub fn new(tz: Option<&String>) -> Self {
-        let mut clock = Self::default();
-        if let Some(tz) = tz {
-            if let Ok(tz) = tz.parse() {
-                clock.tz = tz;
+    pub fn new(tz: Tz, format: &str, numerals: Numerals) -> Self {
+        Self {
+            tz,
+            format: format.to_string(),
+            numerals,
+        }
+    }
+
+    fn formatted(&self, format: &str) -> String {
+        let now = time::now();
+        // NOTE: only the epoch is grouped, years and times would read wrong
+        let format = format.replace("%s", &self.numerals.group(&now.timestamp().to_string()));
+        let text = now.with_timezone(&self.tz).format(&format).to_string();
+        self.numerals.digits(&text)
+    }
+}
+
+// INFO: `%H:%M:%S` becomes `%H:%M`, the epoch is dropped along with the words labelling it -
+// `epoch: %s`
+pub fn without_seconds(format: &str) -> String {
+    let mut words: Vec<&str> = Vec::new();
+    for word in format.split(' ') {
+        if word.contains("%s") {
+            while words.last().is_some_and(|x| !x.contains('%')) {
+                words.pop();
             }
+        } else {
+            words.push(word);
+        }
+    }
+    words
+        .join(" ")
+        .replace(":%S", "")
+        .replace("%S", "")
+        .replace("%T", "%H:%M")
+        .replace("%X", "%H:%M")
+        .replace("%r", "%I:%M %p")
+        .replace("%c", "%a %b %e %H:%M %Y")
+        .replace("%+", "%Y-%m-%dT%H:%M%:z")
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Local};
+
+    use super::*;
+
+    #[test]
+    fn compact_default_format_holds_within_a_minute() {
+        let clock = Clock::default();
+        let compact = without_seconds(DEFAULT_FORMAT);
+        let at = |instant: &str| {
+            let instant = DateTime::parse_from_rfc3339(instant).unwrap();
+            time::set_fixed(Some(instant.with_timezone(&Local)));
+            clock.formatted(&compact)
         };
-        clock
+        assert_eq!(at("2024-01-01T12:00:05Z"), at("2024-01-01T12:00:55Z"));
+        assert_ne!(at("2024-01-01T12:00:55Z"), at("2024-01-01T12:01:05Z"));
+    }
+
+    #[test]
+    fn drops_seconds_of_every_specifier() {
+        for format in SECONDS {
+            assert!(!SECONDS.iter().any(|x| without_seconds(format).contains(x)));
+        }
     }
 }
@@ -0,0 +1,98 @@
+use std::collections::BTreeMap;
+
+use anstyle::Style as AnsiStyle;
+use zellij_tile::prelude::*;
+
+use crate::{glyph, human::HumanFormat, time, widget::Widget, Palette, Segment};
+
+// NOTE: duration of the last command finished in the focused pane, reported by shell hooks
+// through `statusline::cmd` messages
+#[derive(Default)]
+pub struct CmdDuration {
+    started: BTreeMap<u32, f64>,
+    finished: BTreeMap<u32, (f64, Option<i32>)>,
+    active: Option<usize>,
+    // NOTE: focused terminal pane by tab position
+    panes: BTreeMap<usize, u32>,
+    pub min_seconds: f64,
+    pub human: HumanFormat,
+}
+
+impl Widget for CmdDuration {
+    fn interested_events(&self) -> Vec<EventType> {
+        vec![EventType::TabUpdate, EventType::PaneUpdate]
+    }
+
+    // INFO: returns `true` when another pane got focused
+    fn update(&mut self, event: &Event) -> bool {
+        let focused = self.focused();
+        match event {
+            Event::TabUpdate(tabs) => {
+                self.active = tabs.iter().find(|x| x.active).map(|x| x.position);
+            }
+            Event::PaneUpdate(manifest) => {
+                self.panes = manifest
+                    .panes
+                    .iter()
+                    .filter_map(|(tab, panes)| {
+                        let pane = panes.iter().find(|x| x.is_focused && !x.is_plugin)?;
+                        Some((*tab, pane.id))
+                    })
+                    .collect();
+            }
+            _ => return false,
+        }
+        focused != self.focused()
+    }
+
+    fn render(&self, palette: &Palette) -> Segment {
+        let Some((seconds, exit)) = self.focused().and_then(|x| self.finished.get(&x)) else {
+            return Segment::empty();
+        };
+        if *seconds < self.min_seconds {
+            return Segment::empty();
+        }
+        let failed = exit.is_some_and(|x| x != 0);
+        let bg = if failed { palette.red } else { palette.gray };
+        let segment = Segment::new(
+            Box::new(format!(
+                "{} {}",
+                glyph("󱎫", "took"),
+                self.human.duration(*seconds)
+            )),
+            AnsiStyle::new().fg_color(palette.black).bg_color(bg),
+            palette,
+        );
+        if failed {
+            segment.struck()
+        } else {
+            segment
+        }
+    }
+}
+
+impl CmdDuration {
+    // INFO: `at` is the epoch time in seconds reported by the shell, the time the message
+    // arrived otherwise
+    pub fn start(&mut self, pane: u32, at: Option<f64>) {
+        self.started.insert(pane, at.unwrap_or_else(now));
+    }
+
+    // INFO: returns `true` when the focused pane finished a command
+    pub fn end(&mut self, pane: u32, at: Option<f64>, exit: Option<i32>) -> bool {
+        let Some(started) = self.started.remove(&pane) else {
+            return false;
+        };
+        let seconds = (at.unwrap_or_else(now) - started).max(0.0);
+        self.finished.insert(pane, (seconds, exit));
+        self.focused() == Some(pane)
+    }
+
+    fn focused(&self) -> Option<u32> {
+        self.active.and_then(|x| self.panes.get(&x)).copied()
+    }
+}
+
+fn now() -> f64 {
+    time::now().timestamp_millis() as f64 / 1000.0
+}
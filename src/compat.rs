@@ -0,0 +1,78 @@
+use std::{cell::Cell, collections::BTreeMap};
+
+use zellij_tile::prelude::*;
+
+// NOTE: zellij releases introducing the plugin APIs called behind the `pipes` and `web-requests`
+// cargo features. The features gate the calls and permissions only, types of zellij-tile 0.40
+// like `PipeMessage` and the resurrectable sessions of `SessionUpdate` are used unconditionally.
+const PIPES_SINCE: (u32, u32, u32) = (0, 40, 0);
+const WEB_REQUESTS_SINCE: (u32, u32, u32) = (0, 39, 0);
+
+thread_local! {
+    // NOTE: version of the running zellij, unknown (headless renders) means every API is there
+    static VERSION: Cell<Option<(u32, u32, u32)>> = const { Cell::new(None) };
+}
+
+// INFO: `0.40.1` -> `(0, 40, 1)`, pre-release suffixes are ignored
+pub fn detect() {
+    let version = get_zellij_version();
+    let mut parts = version.split(['.', '-']).map(|x| x.parse::<u32>().ok());
+    let mut next = || parts.next().flatten();
+    let parsed = next().zip(next()).zip(next()).map(|((x, y), z)| (x, y, z));
+    VERSION.with(|x| x.set(parsed));
+}
+
+fn supports(since: (u32, u32, u32)) -> bool {
+    VERSION.with(Cell::get).is_none_or(|x| x >= since)
+}
+
+pub fn pipes() -> bool {
+    cfg!(feature = "pipes") && supports(PIPES_SINCE)
+}
+
+pub fn web_requests() -> bool {
+    cfg!(feature = "web-requests") && supports(WEB_REQUESTS_SINCE)
+}
+
+// NOTE: older hosts reject the whole request when it contains a permission they don't know
+pub fn permissions() -> Vec<PermissionType> {
+    let mut permissions = vec![
+        PermissionType::ReadApplicationState,
+        PermissionType::ChangeApplicationState,
+        PermissionType::RunCommands,
+    ];
+    if pipes() {
+        permissions.push(PermissionType::ReadCliPipes);
+    }
+    if web_requests() {
+        permissions.push(PermissionType::WebAccess);
+    }
+    permissions
+}
+
+// INFO: does nothing on hosts without web requests, callers check `web_requests` up front
+pub fn web_request(url: &str, context: BTreeMap<String, String>) {
+    #[cfg(feature = "web-requests")]
+    if web_requests() {
+        zellij_tile::prelude::web_request(url, HttpVerb::Get, BTreeMap::new(), vec![], context);
+    }
+    #[cfg(not(feature = "web-requests"))]
+    let _ = (url, context);
+}
+
+pub fn cli_pipe_output(pipe_id: &str, output: &str) {
+    #[cfg(feature = "pipes")]
+    if pipes() {
+        zellij_tile::prelude::cli_pipe_output(pipe_id, output);
+    }
+    #[cfg(not(feature = "pipes"))]
+    let _ = (pipe_id, output);
+}
+
+// INFO: sessions of a `SessionUpdate`, the resurrectable ones sent since 0.40 are left out
+pub fn sessions(event: &Event) -> Option<&[SessionInfo]> {
+    match event {
+        Event::SessionUpdate(sessions, _) => Some(sessions),
+        _ => None,
+    }
+}
@@ -0,0 +1,530 @@
+use std::{collections::BTreeMap, fmt::Display, fs, io, str::FromStr};
+
+use chrono::{DateTime, Local, NaiveTime};
+use chrono_tz::Tz;
+use serde::{
+    de::{self, value::MapDeserializer},
+    Deserialize, Deserializer,
+};
+
+use crate::{
+    clock::DEFAULT_FORMAT,
+    human::{DurationStyle, Units},
+    numerals::Numerals,
+    parse_color, placeholders, profiles, script, text, theme, visibility, Align, Truncate,
+};
+
+// NOTE: prefix of style variables - `var_accent = "#f38ba8"` is referenced as `$accent`
+const VAR_PREFIX: &str = "var_";
+// NOTE: keys holding colors, the only ones style variables are substituted in - commands, texts
+// and scripts keep their `$` as it is
+const VAR_SUFFIXES: &[&str] = &[theme::FG_SUFFIX, theme::BG_SUFFIX, "_colors"];
+
+// NOTE: families of keys read by their own modules - style variables, color rules, breakpoints,
+// buttons, label overrides and profile layouts
+const KEY_PREFIXES: &[&str] = &[
+    "var_",
+    "rule_",
+    "hide_below_",
+    "button_",
+    "label_",
+    profiles::LAYOUT_PREFIX,
+];
+
+// NOTE: plugin data dir is mounted at `/data` inside the plugin sandbox
+pub const DUMP_PATH: &str = "/data/statusline.kdl";
+
+// NOTE: default values of every configuration key the plugin understands
+const DEFAULTS: &[(&str, &str)] = &[
+    ("preset", "default"),
+    ("timezone", "UTC"),
+    ("clock_format", DEFAULT_FORMAT),
+    ("separator", "angled"),
+    ("nerd_fonts", "true"),
+    ("extended_sgr", "false"),
+    ("ellipsis", "..."),
+    ("padding_left", "1"),
+    ("padding_right", "1"),
+    ("align", "center"),
+    ("tab_index", "decimal"),
+    ("selectable", "false"),
+    ("autohide", "false"),
+    ("accessible", "false"),
+    ("contrast_check", "false"),
+    ("errors_expanded", "false"),
+    ("debug", "false"),
+    ("mode_breadcrumb", "false"),
+    ("lang", "en"),
+    ("sparkline_width", "8"),
+    ("units", "si"),
+    ("decimal_separator", "."),
+    ("duration_style", "compact"),
+    ("numeral_locale", "none"),
+    ("battery", "false"),
+    ("battery_critical", "10"),
+    ("battery_interval", "60"),
+    ("power_interval", "30"),
+    ("battery_poll_factor", "1"),
+    ("low_power", "off"),
+    ("updates_interval", "86400"),
+    ("auth", "false"),
+    ("auth_interval", "300"),
+    ("http_interval", "60"),
+    ("env_interval", "300"),
+    ("scope", "session"),
+    ("cmd_duration_min", "2"),
+    ("git", "false"),
+    ("git_interval", "10"),
+    ("floating", "false"),
+    ("mode_usage", "false"),
+    ("history_size", "20"),
+    ("pipe_max_width", "64"),
+    ("use_zellij_theme", "false"),
+    ("locked_palette", "false"),
+    ("client_toasts", "true"),
+    ("margin_left", "0"),
+    ("margin_right", "0"),
+    ("valign", "top"),
+    ("appearance_interval", "300"),
+    ("tab_suggestions", "false"),
+    ("tab_stale_minutes", "0"),
+    ("tab_flash_seconds", "1.5"),
+    ("toast_area", "auto"),
+    ("toast_align", "left"),
+];
+
+// NOTE: typed view of the effective configuration, every key the plugin understands except for
+// the prefixed families kept in `other`
+#[derive(Deserialize)]
+pub struct Config {
+    #[serde(deserialize_with = "preset")]
+    pub preset: String,
+    #[serde(deserialize_with = "parsed")]
+    pub timezone: Tz,
+    pub clock_format: String,
+    pub separator: String,
+    #[serde(default, deserialize_with = "some")]
+    pub separator_right: Option<String>,
+    #[serde(deserialize_with = "parsed")]
+    pub nerd_fonts: bool,
+    #[serde(deserialize_with = "parsed")]
+    pub extended_sgr: bool,
+    pub ellipsis: String,
+    #[serde(deserialize_with = "parsed")]
+    pub padding_left: usize,
+    #[serde(deserialize_with = "parsed")]
+    pub padding_right: usize,
+    #[serde(deserialize_with = "parsed")]
+    pub align: Align,
+    pub tab_index: String,
+    #[serde(deserialize_with = "parsed")]
+    pub selectable: bool,
+    #[serde(deserialize_with = "parsed")]
+    pub autohide: bool,
+    #[serde(deserialize_with = "parsed")]
+    pub accessible: bool,
+    #[serde(deserialize_with = "parsed")]
+    pub contrast_check: bool,
+    #[serde(deserialize_with = "parsed")]
+    pub errors_expanded: bool,
+    #[serde(deserialize_with = "parsed")]
+    pub debug: bool,
+    #[serde(deserialize_with = "parsed")]
+    pub mode_breadcrumb: bool,
+    pub lang: String,
+    #[serde(deserialize_with = "parsed")]
+    pub sparkline_width: usize,
+    pub units: Units,
+    #[serde(deserialize_with = "parsed")]
+    pub decimal_separator: char,
+    pub duration_style: DurationStyle,
+    #[serde(deserialize_with = "parsed")]
+    pub numeral_locale: Numerals,
+    #[serde(default, deserialize_with = "instant")]
+    pub fixed_time: Option<DateTime<Local>>,
+    #[serde(default, deserialize_with = "time_of_day")]
+    pub weekly_summary_at: Option<NaiveTime>,
+    #[serde(default, deserialize_with = "some")]
+    pub zellij_theme_file: Option<String>,
+    #[serde(default, deserialize_with = "some")]
+    pub config_file: Option<String>,
+    #[serde(default, deserialize_with = "some")]
+    pub cap_left: Option<String>,
+    #[serde(default, deserialize_with = "some")]
+    pub cap_right: Option<String>,
+    #[serde(default, deserialize_with = "some")]
+    pub layout: Option<String>,
+    #[serde(default, deserialize_with = "some")]
+    pub left: Option<String>,
+    #[serde(default, deserialize_with = "some")]
+    pub center: Option<String>,
+    #[serde(default, deserialize_with = "some")]
+    pub right: Option<String>,
+    #[serde(default, deserialize_with = "some")]
+    pub fill_on_click: Option<String>,
+    pub scope: Scope,
+    #[serde(default, deserialize_with = "some")]
+    pub workspace: Option<String>,
+    #[serde(default, deserialize_with = "some")]
+    pub workspace_colors: Option<String>,
+    #[serde(deserialize_with = "parsed")]
+    pub battery: bool,
+    #[serde(deserialize_with = "parsed")]
+    pub battery_critical: u8,
+    #[serde(default, deserialize_with = "some")]
+    pub battery_command: Option<String>,
+    #[serde(deserialize_with = "parsed")]
+    pub battery_interval: f64,
+    #[serde(deserialize_with = "parsed")]
+    pub power_interval: f64,
+    #[serde(deserialize_with = "parsed")]
+    pub battery_poll_factor: f64,
+    #[serde(default, deserialize_with = "some")]
+    pub battery_profile: Option<String>,
+    pub low_power: LowPower,
+    #[serde(default, deserialize_with = "some")]
+    pub updates_check: Option<String>,
+    #[serde(default, deserialize_with = "some")]
+    pub updates_command: Option<String>,
+    #[serde(deserialize_with = "parsed")]
+    pub updates_interval: f64,
+    #[serde(deserialize_with = "parsed")]
+    pub auth: bool,
+    #[serde(default, deserialize_with = "some")]
+    pub auth_check: Option<String>,
+    #[serde(deserialize_with = "parsed")]
+    pub auth_interval: f64,
+    #[serde(default, deserialize_with = "some")]
+    pub http_url: Option<String>,
+    #[serde(default, deserialize_with = "some")]
+    pub http_path: Option<String>,
+    #[serde(default, deserialize_with = "some")]
+    pub http_colors: Option<String>,
+    #[serde(deserialize_with = "parsed")]
+    pub http_interval: f64,
+    #[serde(default, deserialize_with = "some")]
+    pub env_vars: Option<String>,
+    #[serde(default, deserialize_with = "some")]
+    pub env_colors: Option<String>,
+    #[serde(deserialize_with = "parsed")]
+    pub env_interval: f64,
+    #[serde(deserialize_with = "parsed")]
+    pub cmd_duration_min: f64,
+    #[serde(deserialize_with = "parsed")]
+    pub git: bool,
+    #[serde(deserialize_with = "parsed")]
+    pub git_interval: f64,
+    #[serde(deserialize_with = "parsed")]
+    pub floating: bool,
+    #[serde(deserialize_with = "parsed")]
+    pub mode_usage: bool,
+    #[serde(deserialize_with = "parsed")]
+    pub history_size: usize,
+    #[serde(deserialize_with = "parsed")]
+    pub pipe_max_width: usize,
+    #[serde(deserialize_with = "parsed")]
+    pub use_zellij_theme: bool,
+    #[serde(deserialize_with = "parsed")]
+    pub locked_palette: bool,
+    #[serde(deserialize_with = "parsed")]
+    pub client_toasts: bool,
+    #[serde(deserialize_with = "parsed")]
+    pub margin_left: usize,
+    #[serde(deserialize_with = "parsed")]
+    pub margin_right: usize,
+    pub valign: VAlign,
+    pub toast_area: ToastArea,
+    pub toast_align: HAlign,
+    #[serde(deserialize_with = "parsed")]
+    pub tab_suggestions: bool,
+    #[serde(deserialize_with = "parsed")]
+    pub tab_stale_minutes: f64,
+    #[serde(deserialize_with = "parsed")]
+    pub tab_flash_seconds: f64,
+    #[serde(default, deserialize_with = "some")]
+    pub text: Option<String>,
+    #[serde(default, deserialize_with = "some")]
+    pub script: Option<String>,
+    #[serde(default, deserialize_with = "some")]
+    pub appearance_command: Option<String>,
+    #[serde(deserialize_with = "parsed")]
+    pub appearance_interval: f64,
+    #[serde(default, deserialize_with = "some")]
+    pub light_theme_file: Option<String>,
+    #[serde(default, deserialize_with = "some")]
+    pub dark_theme_file: Option<String>,
+    #[serde(flatten)]
+    pub other: BTreeMap<String, String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        parse(&effective(&BTreeMap::new())).0
+    }
+}
+
+#[derive(Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Scope {
+    Session,
+    Tab,
+}
+
+// NOTE: row of a plugin pane taller than one row occupied by the bar
+#[derive(Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum VAlign {
+    Top,
+    Middle,
+    Bottom,
+}
+
+// NOTE: transients get a row of their own on panes of 2+ rows with `auto`
+#[derive(Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ToastArea {
+    Auto,
+    Inline,
+}
+
+// NOTE: `auto` follows the power source, see the `power` element
+#[derive(Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum LowPower {
+    Off,
+    On,
+    Auto,
+}
+
+#[derive(Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum HAlign {
+    Left,
+    Center,
+    Right,
+}
+
+// NOTE: keys are checked one at a time on top of the defaults, so a bad value only falls back
+// to its default and every problem gets its own diagnostic
+pub fn parse(config: &BTreeMap<String, String>) -> (Config, Vec<String>) {
+    let defaults = effective(&BTreeMap::new());
+    let mut valid = defaults.clone();
+    let mut diagnostics = Vec::new();
+    for (key, value) in config {
+        let mut single = defaults.clone();
+        single.insert(key.clone(), value.clone());
+        match deserialize(&single) {
+            Ok(_) => {
+                valid.insert(key.clone(), value.clone());
+            }
+            Err(err) => diagnostics.push(format!("{key}: {err}")),
+        }
+    }
+
+    // INFO: every key left deserialized on its own along with the defaults
+    let config = deserialize(&valid).expect("validated configuration");
+    for (key, value) in &config.other {
+        if key.ends_with(theme::FG_SUFFIX) || key.ends_with(theme::BG_SUFFIX) {
+            if parse_color(value).is_none() {
+                diagnostics.push(format!("{key}: invalid color `{value}`"));
+            }
+        } else if key.ends_with(theme::TRUNCATE_SUFFIX) {
+            if let Err(err) = value.parse::<Truncate>() {
+                diagnostics.push(format!("{key}: invalid value `{value}` ({err})"));
+            }
+        } else if key.ends_with(visibility::SUFFIX) {
+            if let Err(err) = visibility::validate(value) {
+                diagnostics.push(format!("{key}: {err}"));
+            }
+        } else if key == VAR_PREFIX {
+            diagnostics.push(format!("{key}: empty variable name"));
+        } else if key.ends_with(placeholders::SUFFIX) {
+            // INFO: any text goes
+        } else if key.starts_with(profiles::PREFIX) {
+            if let Err(err) = profiles::validate(key, value, &config.other) {
+                diagnostics.push(format!("{key}: {err}"));
+            }
+        } else if !KEY_PREFIXES.iter().any(|x| key.starts_with(x)) {
+            diagnostics.push(format!("{key}: unknown key"));
+        }
+    }
+    let maps = [
+        ("workspace_colors", &config.workspace_colors),
+        ("env_colors", &config.env_colors),
+        ("http_colors", &config.http_colors),
+    ];
+    for (key, value) in maps {
+        let colors = value
+            .iter()
+            .flat_map(|x| x.split(','))
+            .filter_map(|x| x.split_once(':'))
+            .map(|(_, color)| color.trim());
+        for color in colors.filter(|x| parse_color(x).is_none()) {
+            diagnostics.push(format!("{key}: invalid color `{color}`"));
+        }
+    }
+    for name in config
+        .text
+        .as_deref()
+        .map(text::unknown)
+        .unwrap_or_default()
+    {
+        diagnostics.push(format!("text: unknown variable `{{{name}}}`"));
+    }
+    if let Some(Err(err)) = config.script.as_deref().map(script::validate) {
+        diagnostics.push(format!("script: {err}"));
+    }
+    if let Some(Err(err)) = config
+        .battery_profile
+        .as_deref()
+        .map(|x| profiles::validate_name(x, &config.other))
+    {
+        diagnostics.push(format!("battery_profile: {err}"));
+    }
+    (config, diagnostics)
+}
+
+fn deserialize(config: &BTreeMap<String, String>) -> Result<Config, de::value::Error> {
+    let entries = config
+        .iter()
+        .map(|(key, value)| (key.as_str(), value.as_str()));
+    Config::deserialize(MapDeserializer::new(entries))
+}
+
+fn parsed<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: Display,
+{
+    let value = String::deserialize(deserializer)?;
+    value
+        .parse()
+        .map_err(|err| de::Error::custom(format!("invalid value `{value}` ({err})")))
+}
+
+// INFO: any text - plain strings don't tell serde an option is set on their own
+fn some<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+    String::deserialize(deserializer).map(Some)
+}
+
+// INFO: RFC 3339 instant - `2024-01-01T12:00:00Z`
+fn instant<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<DateTime<Local>>, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    DateTime::parse_from_rfc3339(&value)
+        .map(|x| Some(x.with_timezone(&Local)))
+        .map_err(|err| de::Error::custom(format!("invalid value `{value}` ({err})")))
+}
+
+// INFO: name of one of the `PRESETS`
+fn preset<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    if !presets().any(|x| x == value) {
+        let names: Vec<&str> = presets().collect();
+        return Err(de::Error::custom(format!(
+            "unknown preset `{value}` (expected one of {})",
+            names.join(", ")
+        )));
+    }
+    Ok(value)
+}
+
+// INFO: `%H:%M` - `09:30`
+fn time_of_day<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<NaiveTime>, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    NaiveTime::parse_from_str(&value, "%H:%M")
+        .map(Some)
+        .map_err(|err| de::Error::custom(format!("invalid value `{value}` ({err})")))
+}
+
+// NOTE: presets only provide defaults - every key set explicitly by the user wins
+const PRESETS: &[(&str, &[(&str, &str)])] = &[
+    ("default", &[]),
+    (
+        "minimal",
+        &[("separator", "none"), ("clock_format", "%H:%M")],
+    ),
+    (
+        "powerline",
+        &[("separator", "angled"), ("clock_format", "%a %d %b  %H:%M")],
+    ),
+];
+
+pub fn presets() -> impl Iterator<Item = &'static str> {
+    PRESETS.iter().map(|(name, _)| *name)
+}
+
+pub fn effective(config: &BTreeMap<String, String>) -> BTreeMap<String, String> {
+    let mut effective: BTreeMap<String, String> = DEFAULTS
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect();
+
+    // INFO: expand the selected preset on top of the defaults
+    let preset = config
+        .get("preset")
+        .map(String::as_str)
+        .unwrap_or("default");
+    if let Some((_, values)) = PRESETS.iter().find(|(name, _)| *name == preset) {
+        effective.extend(
+            values
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string())),
+        );
+    }
+
+    effective.extend(config.clone());
+
+    // INFO: resolve `$name` references to style variables in color values
+    let vars: BTreeMap<String, String> = effective
+        .iter()
+        .filter_map(|(key, value)| Some((key.strip_prefix(VAR_PREFIX)?, value)))
+        .filter(|(name, _)| !name.is_empty())
+        .map(|(name, value)| (name.to_string(), value.clone()))
+        .collect();
+    if !vars.is_empty() {
+        for (key, value) in effective.iter_mut() {
+            if VAR_SUFFIXES.iter().any(|x| key.ends_with(x)) {
+                *value = resolve(value, &vars);
+            }
+        }
+    }
+    effective
+}
+
+fn resolve(value: &str, vars: &BTreeMap<String, String>) -> String {
+    let mut resolved = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find('$') {
+        resolved += &rest[..start];
+        let name_len = rest[start + 1..]
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .unwrap_or(rest.len() - start - 1);
+        let name = &rest[start + 1..start + 1 + name_len];
+        match vars.get(name) {
+            Some(var) => resolved += var,
+            None => resolved += &rest[start..start + 1 + name_len],
+        }
+        rest = &rest[start + 1 + name_len..];
+    }
+    resolved + rest
+}
+
+pub fn to_kdl(config: &BTreeMap<String, String>) -> String {
+    let mut kdl = String::from("plugin location=\"file:zellij-statusline.wasm\" {\n");
+    for (key, value) in config {
+        kdl += &format!(
+            "    {key} \"{}\"\n",
+            value.replace('\\', "\\\\").replace('"', "\\\"")
+        );
+    }
+    kdl += "}\n";
+    kdl
+}
+
+pub fn dump(config: &BTreeMap<String, String>) -> io::Result<&'static str> {
+    fs::write(DUMP_PATH, to_kdl(&effective(config)))?;
+    Ok(DUMP_PATH)
+}
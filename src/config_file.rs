@@ -0,0 +1,131 @@
+use std::{collections::BTreeMap, fs};
+
+use kdl::{KdlDocument, KdlNode, KdlValue};
+
+use crate::theme::host_path;
+
+// NOTE: structured configuration read from `config_file`, e.g.
+// separator "round"
+// layout {
+//     left "mode" "session" "tabs"
+//     right "custom" "battery" "clock"
+// }
+// theme {
+//     file "themes/catppuccin.kdl"
+//     accent "#f38ba8"
+// }
+// widgets {
+//     battery enabled=true critical=15
+//     clock {
+//         format "%H:%M"
+//     }
+// }
+#[derive(Default)]
+pub struct ConfigFile {
+    pub settings: BTreeMap<String, String>,
+    // INFO: left, center and right groups of element names
+    pub layout: Option<[Vec<String>; 3]>,
+    pub theme: BTreeMap<String, String>,
+    pub widgets: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+impl ConfigFile {
+    pub fn load(path: &str) -> Result<Self, String> {
+        let content =
+            fs::read_to_string(host_path(path)?).map_err(|err| format!("{path}: {err}"))?;
+        let document: KdlDocument = content.parse().map_err(|err| format!("{path}: {err}"))?;
+
+        let mut file = Self::default();
+        for node in document.nodes() {
+            match node.name().value() {
+                "layout" => {
+                    let mut layout: [Vec<String>; 3] = Default::default();
+                    for group in children(node) {
+                        let index = match group.name().value() {
+                            "left" => 0,
+                            "center" => 1,
+                            "right" => 2,
+                            name => return Err(format!("{path}: unknown layout group `{name}`")),
+                        };
+                        layout[index] = group.entries().iter().map(|x| text(x.value())).collect();
+                    }
+                    file.layout = Some(layout);
+                }
+                "theme" => file.theme.extend(options(node)),
+                "widgets" => {
+                    for widget in children(node) {
+                        file.widgets
+                            .entry(widget.name().value().to_string())
+                            .or_default()
+                            .extend(options(widget));
+                    }
+                }
+                key => {
+                    if let Some(entry) = node.entries().first() {
+                        file.settings.insert(key.to_string(), text(entry.value()));
+                    }
+                }
+            }
+        }
+        Ok(file)
+    }
+
+    // INFO: flat keys understood by the rest of the plugin - `theme { file }` is
+    // `zellij_theme_file`, other theme entries are style variables, widget options are prefixed
+    // with the widget name and `enabled` turns the widget itself on
+    pub fn flatten(self) -> BTreeMap<String, String> {
+        let mut config = self.settings;
+        if let Some([left, center, right]) = self.layout {
+            config.insert(
+                "layout".to_string(),
+                format!(
+                    "{} | {} | {}",
+                    left.join(" "),
+                    center.join(" "),
+                    right.join(" ")
+                ),
+            );
+        }
+        for (key, value) in self.theme {
+            let key = match key.as_str() {
+                "file" => "zellij_theme_file".to_string(),
+                _ => format!("var_{key}"),
+            };
+            config.insert(key, value);
+        }
+        for (widget, options) in self.widgets {
+            for (key, value) in options {
+                let key = match key.as_str() {
+                    "enabled" => widget.clone(),
+                    _ => format!("{widget}_{key}"),
+                };
+                config.insert(key, value);
+            }
+        }
+        config
+    }
+}
+
+fn children(node: &KdlNode) -> &[KdlNode] {
+    node.children().map(KdlDocument::nodes).unwrap_or_default()
+}
+
+// INFO: both properties (`critical=15`) and child nodes (`critical 15`) are options
+fn options(node: &KdlNode) -> BTreeMap<String, String> {
+    let properties = node
+        .entries()
+        .iter()
+        .filter_map(|x| Some((x.name()?.value().to_string(), text(x.value()))));
+    let nodes = children(node).iter().filter_map(|x| {
+        let entry = x.entries().first()?;
+        Some((x.name().value().to_string(), text(entry.value())))
+    });
+    properties.chain(nodes).collect()
+}
+
+pub fn text(value: &KdlValue) -> String {
+    match value.as_string() {
+        Some(value) => value.to_string(),
+        None => value.to_string(),
+    }
+}
@@ -0,0 +1,60 @@
+use anstyle::Style as AnsiStyle;
+use chrono::{DateTime, Duration, Local};
+use zellij_tile::prelude::*;
+
+use crate::{i18n, menu::Selection, time, Palette, Segment};
+
+// NOTE: destructive actions have to be confirmed with a second click before `timeout` expires
+pub struct Confirm<A> {
+    prompt: String,
+    action: A,
+    timeout: f64,
+    expires: DateTime<Local>,
+}
+
+impl<A: Clone> Confirm<A> {
+    pub fn render(&self, palette: &Palette) -> String {
+        Segment::new(
+            Box::new(format!(
+                "{} {}",
+                self.prompt,
+                i18n::tr("click_again").replace("{seconds}", &self.timeout.to_string())
+            )),
+            AnsiStyle::new()
+                .fg_color(palette.black)
+                .bg_color(palette.red)
+                .bold(),
+            palette,
+        )
+        .max_width(128)
+        .to_string()
+    }
+
+    pub fn new(prompt: String, action: A, timeout: f64) -> Self {
+        Self {
+            prompt,
+            action,
+            timeout,
+            expires: time::now() + Duration::milliseconds((timeout * 1000.0) as i64),
+        }
+    }
+
+    // INFO: `None` when the event isn't meant for the prompt
+    pub fn handle(&self, event: &Event) -> Option<Selection<A>> {
+        let expired = time::now() > self.expires;
+        match event {
+            Event::Timer(_) if expired => Some(Selection::Dismissed),
+            Event::Mouse(Mouse::LeftClick(..))
+            | Event::Key(Key::Char('\n'))
+            | Event::Key(Key::Char('y'))
+                if !expired =>
+            {
+                Some(Selection::Picked(self.action.clone()))
+            }
+            Event::Mouse(Mouse::LeftClick(..) | Mouse::RightClick(..)) | Event::Key(_) => {
+                Some(Selection::Dismissed)
+            }
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use anstyle::{Ansi256Color, Color, RgbColor};
+
+// NOTE: WCAG AA minimum for normal sized text
+pub const MIN_RATIO: f64 = 4.5;
+
+// NOTE: xterm defaults of the 16 ANSI colors, the terminal may map them to anything else
+const ANSI: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+// INFO: `None` when the pair is readable or either color is left to the terminal, a diagnostic
+// with a darker or lighter fg that would pass otherwise
+pub fn check(name: &str, fg: Option<Color>, bg: Option<Color>) -> Option<String> {
+    let (fg, bg) = (rgb(fg?), rgb(bg?));
+    let ratio = ratio(fg, bg);
+    if ratio >= MIN_RATIO {
+        return None;
+    }
+    let (r, g, b) = suggest(fg, bg);
+    Some(format!(
+        "{name}: contrast {ratio:.1}:1 below {MIN_RATIO}:1 (try fg `#{r:02x}{g:02x}{b:02x}`)"
+    ))
+}
+
+pub fn ratio(fg: (u8, u8, u8), bg: (u8, u8, u8)) -> f64 {
+    let (a, b) = (luminance(fg), luminance(bg));
+    (a.max(b) + 0.05) / (a.min(b) + 0.05)
+}
+
+// INFO: `fg` mixed towards black or white, whichever stands out more, in steps of 10% until
+// readable - one of the two always is
+fn suggest(fg: (u8, u8, u8), bg: (u8, u8, u8)) -> (u8, u8, u8) {
+    let target = if ratio((0, 0, 0), bg) > ratio((255, 255, 255), bg) {
+        (0, 0, 0)
+    } else {
+        (255, 255, 255)
+    };
+    let mix =
+        |from: u8, to: u8, step: u16| ((from as u16 * (10 - step) + to as u16 * step) / 10) as u8;
+    (1..=10)
+        .map(|step| {
+            (
+                mix(fg.0, target.0, step),
+                mix(fg.1, target.1, step),
+                mix(fg.2, target.2, step),
+            )
+        })
+        .find(|x| ratio(*x, bg) >= MIN_RATIO)
+        .unwrap_or(target)
+}
+
+fn luminance((r, g, b): (u8, u8, u8)) -> f64 {
+    let channel = |x: u8| {
+        let x = x as f64 / 255.0;
+        if x <= 0.03928 {
+            x / 12.92
+        } else {
+            ((x + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    0.2126 * channel(r) + 0.7152 * channel(g) + 0.0722 * channel(b)
+}
+
+fn rgb(color: Color) -> (u8, u8, u8) {
+    match color {
+        Color::Ansi(x) => rgb(Ansi256Color::from_ansi(x).into()),
+        Color::Ansi256(Ansi256Color(index)) => match index {
+            0..=15 => ANSI[index as usize],
+            16..=231 => {
+                let level = |x: u8| if x == 0 { 0 } else { 55 + x * 40 };
+                let index = index - 16;
+                (level(index / 36), level(index / 6 % 6), level(index % 6))
+            }
+            _ => {
+                let gray = 8 + (index - 232) * 10;
+                (gray, gray, gray)
+            }
+        },
+        Color::Rgb(RgbColor(r, g, b)) => (r, g, b),
+    }
+}
@@ -0,0 +1,122 @@
+use std::collections::BTreeMap;
+
+use anstyle::Style as AnsiStyle;
+use chrono::{DateTime, Duration, Local};
+use zellij_tile::prelude::*;
+
+use crate::{sparkline::Sparkline, time, widget::Widget, Palette, Segment};
+
+struct Entry {
+    text: String,
+    style: AnsiStyle,
+    expires: Option<DateTime<Local>>,
+    history: Option<Sparkline>,
+    hidden: bool,
+}
+
+// NOTE: segments created at runtime through pipes
+#[derive(Default)]
+pub struct Custom {
+    segments: BTreeMap<String, Entry>,
+    pub sparkline_width: usize,
+}
+
+impl Widget for Custom {
+    fn interested_events(&self) -> Vec<EventType> {
+        vec![EventType::Timer]
+    }
+
+    // INFO: returns `true` when any segment expired
+    fn update(&mut self, event: &Event) -> bool {
+        let Event::Timer(_) = event else {
+            return false;
+        };
+        let now = time::now();
+        let count = self.segments.len();
+        self.segments
+            .retain(|_, entry| entry.expires.is_none_or(|x| x > now));
+        count != self.segments.len()
+    }
+
+    fn render(&self, palette: &Palette) -> Segment {
+        let segments: String = self
+            .segments
+            .values()
+            .filter(|x| !x.hidden)
+            .map(|x| Segment::new(Box::new(x.text.clone()), x.style, palette).to_string())
+            .collect();
+        Segment::raw(Box::new(segments))
+    }
+}
+
+impl Custom {
+    // INFO: numeric `value` is appended to the segment history and shown as a sparkline
+    pub fn set(
+        &mut self,
+        name: String,
+        text: String,
+        style: AnsiStyle,
+        ttl: Option<f64>,
+        value: Option<f64>,
+    ) {
+        let expires = ttl.and_then(|x| {
+            time::now().checked_add_signed(Duration::milliseconds((x * 1000.0) as i64))
+        });
+        let (mut history, hidden) = self
+            .segments
+            .remove(&name)
+            .map_or((None, false), |x| (x.history, x.hidden));
+        if let Some(value) = value {
+            history
+                .get_or_insert_with(|| Sparkline::new(self.sparkline_width))
+                .push(value);
+        }
+
+        let content = match &history {
+            Some(history) => format!("{text} {history}"),
+            None => text,
+        };
+        self.segments.insert(
+            name,
+            Entry {
+                text: content,
+                style,
+                expires,
+                history,
+                hidden,
+            },
+        );
+    }
+
+    // INFO: plain text of every segment, used by the accessible output
+    pub fn texts(&self) -> Vec<(&str, &str)> {
+        self.segments
+            .iter()
+            .filter(|(_, entry)| !entry.hidden)
+            .map(|(name, entry)| (name.as_str(), entry.text.as_str()))
+            .collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.segments.values().all(|x| x.hidden)
+    }
+
+    pub fn clear(&mut self) -> bool {
+        let cleared = !self.segments.is_empty();
+        self.segments.clear();
+        cleared
+    }
+
+    pub fn remove(&mut self, name: &str) -> bool {
+        self.segments.remove(name).is_some()
+    }
+
+    // INFO: returns `false` when there's no segment called `name`
+    pub fn set_hidden(&mut self, name: &str, hidden: bool) -> bool {
+        let Some(entry) = self.segments.get_mut(name) else {
+            return false;
+        };
+        entry.hidden = hidden;
+        true
+    }
+}
@@ -0,0 +1,45 @@
+use std::{fmt::Write, ops::Range};
+
+use anstyle::Style as AnsiStyle;
+
+use crate::{truncate, DisplayExt, Palette};
+
+// NOTE: row drawn next to the bar with `debug = true` - every element labelled with the width
+// it reported under its first column, `reported/drawn` when the line disagrees, followed by
+// the cells used out of the ones available
+pub fn overlay(
+    regions: &[(&'static str, Range<usize>)],
+    widths: &[(&'static str, usize)],
+    tabs: Option<&str>,
+    cols: usize,
+    palette: &Palette,
+) -> String {
+    let mut line = String::new();
+    let mut chars = 0;
+    for (name, region) in regions.iter().filter(|(x, _)| *x != "fill") {
+        let Some(width) = widths.iter().find(|(x, _)| x == name).map(|(_, x)| *x) else {
+            continue;
+        };
+        let variant = tabs.filter(|_| *name == "tabs").map(|x| format!(" {x}"));
+        let drawn = region.len();
+        let label = if width == drawn {
+            format!("│{name} {width}{}", variant.unwrap_or_default())
+        } else {
+            format!("│{name} {width}/{drawn}{}", variant.unwrap_or_default())
+        };
+        line.push_str(&" ".repeat(region.start.saturating_sub(chars)));
+        chars = chars.max(region.start);
+        let (label, used) = truncate(&label, region.end.saturating_sub(chars));
+        line.push_str(&label);
+        chars += used;
+    }
+    let used: usize = widths.iter().map(|(_, x)| x).sum();
+    let total = format!(" {used}/{cols}");
+    if chars + total.display_len() <= cols {
+        let _ = write!(line, "{:>1$}", total, cols - chars);
+    }
+    let style = AnsiStyle::new()
+        .fg_color(palette.yellow)
+        .bg_color(palette.bg);
+    format!("{}{line}{}", style.render(), style.render_reset())
+}
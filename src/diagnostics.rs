@@ -0,0 +1,61 @@
+use anstyle::Style as AnsiStyle;
+
+use crate::{widget::Widget, Palette, Segment};
+
+// NOTE: configuration problems shown in front of the bar until clicked away, instead of the
+// offending keys being silently ignored
+#[derive(Default)]
+pub struct Diagnostics {
+    messages: Vec<String>,
+    // NOTE: unreadable color pairs of `contrast_check`, checked again whenever the palette changes
+    warnings: Vec<String>,
+}
+
+impl Widget for Diagnostics {
+    fn render(&self, palette: &Palette) -> Segment {
+        if self.is_empty() {
+            return Segment::empty();
+        }
+        let messages: Vec<&str> = self
+            .messages
+            .iter()
+            .chain(&self.warnings)
+            .map(String::as_str)
+            .collect();
+        Segment::new(
+            Box::new(format!(" {}", messages.join(" · "))),
+            AnsiStyle::new()
+                .fg_color(palette.white)
+                .bg_color(palette.red)
+                .bold(),
+            palette,
+        )
+        .max_width(96)
+        .priority(u8::MAX)
+    }
+}
+
+impl Diagnostics {
+    pub fn set(&mut self, messages: Vec<String>) {
+        self.messages = messages;
+    }
+
+    pub fn push(&mut self, message: String) {
+        self.messages.push(message);
+    }
+
+    pub fn set_warnings(&mut self, warnings: Vec<String>) {
+        self.warnings = warnings;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty() && self.warnings.is_empty()
+    }
+
+    pub fn clear(&mut self) -> bool {
+        let cleared = !self.is_empty();
+        self.messages.clear();
+        self.warnings.clear();
+        cleared
+    }
+}
@@ -0,0 +1,103 @@
+use std::collections::BTreeMap;
+
+use anstyle::{Color, Style as AnsiStyle};
+use zellij_tile::prelude::*;
+
+use crate::{parse_color, poll, sanitize, widget::Widget, Palette, Segment};
+
+// NOTE: the environment zellij was started with, plugins can't read it on their own
+pub const COMMAND: &str = "env";
+
+// NOTE: badges of environment variables the session is bound to - `env_vars = "AWS_PROFILE"`
+// with `env_colors = "prod:red,staging:yellow"` turns production sessions red. Unset variables
+// are left out.
+#[derive(Default)]
+pub struct Environment {
+    names: Vec<String>,
+    colors: Vec<(String, Color)>,
+    values: BTreeMap<String, String>,
+    loaded: bool,
+}
+
+impl Widget for Environment {
+    fn interested_events(&self) -> Vec<EventType> {
+        vec![EventType::RunCommandResult]
+    }
+
+    // INFO: returns `true` when any of the values changed
+    fn update(&mut self, event: &Event) -> bool {
+        let Event::RunCommandResult(_, stdout, _, context) = event else {
+            return false;
+        };
+        if context.get(poll::POLL).map(String::as_str) != Some("env") {
+            return false;
+        }
+        let values: BTreeMap<String, String> = String::from_utf8_lossy(stdout)
+            .lines()
+            .filter_map(|x| x.split_once('='))
+            .filter(|(name, value)| self.names.iter().any(|x| x == name) && !value.is_empty())
+            .map(|(name, value)| (name.to_string(), sanitize::text(value)))
+            .collect();
+        let changed = self.values != values || !self.loaded;
+        self.values = values;
+        self.loaded = true;
+        changed
+    }
+
+    fn is_loaded(&self) -> bool {
+        self.loaded
+    }
+
+    fn render(&self, palette: &Palette) -> Segment {
+        let badges: String = self
+            .names
+            .iter()
+            .filter_map(|name| {
+                let value = self.values.get(name)?;
+                let badge = Segment::new(
+                    Box::new(format!("{} {value}", name.to_lowercase())),
+                    AnsiStyle::new()
+                        .fg_color(palette.black)
+                        .bg_color(self.color(value, palette))
+                        .bold(),
+                    palette,
+                );
+                Some(badge.to_string())
+            })
+            .collect();
+        Segment::raw(Box::new(badges))
+    }
+}
+
+impl Environment {
+    // INFO: `colors` maps parts of values to colors - `prod:red` matches `acme-prod` as well,
+    // invalid colors are left out for the default one
+    pub fn new(names: Option<&String>, colors: Option<&String>) -> Self {
+        let names = names
+            .map(|x| x.split(',').map(|x| x.trim().to_string()).collect())
+            .unwrap_or_default();
+        let colors = colors
+            .map(|x| {
+                x.split(',')
+                    .filter_map(|x| x.split_once(':'))
+                    .filter_map(|(value, color)| {
+                        Some((value.trim().to_lowercase(), parse_color(color)?))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self {
+            names,
+            colors,
+            ..Default::default()
+        }
+    }
+
+    fn color(&self, value: &str, palette: &Palette) -> Option<Color> {
+        let value = value.to_lowercase();
+        self.colors
+            .iter()
+            .find(|(part, _)| value.contains(part.as_str()))
+            .map_or(palette.blue, |(_, color)| Some(*color))
+    }
+}
@@ -0,0 +1,110 @@
+use std::collections::BTreeMap;
+
+use anstyle::Style as AnsiStyle;
+use zellij_tile::prelude::*;
+
+use crate::{poll, sanitize, widget::Widget, worker, Palette, Segment};
+
+// NOTE: polls failing as a regular answer - `defaults read` of a light macOS appearance
+const EXPECTED: &[&str] = &["appearance"];
+
+// NOTE: failed polls - commands exiting with an error, requests answered with an error status
+// and bodies without the value at `http_path` - shown as a red `!` until they succeed again.
+// Clicking it or `errors_expanded` shows the messages.
+#[derive(Default)]
+pub struct Errors {
+    failures: BTreeMap<String, String>,
+    pub expanded: bool,
+}
+
+impl Widget for Errors {
+    fn interested_events(&self) -> Vec<EventType> {
+        vec![
+            EventType::RunCommandResult,
+            EventType::WebRequestResult,
+            EventType::CustomMessage,
+        ]
+    }
+
+    // INFO: returns `true` when a poll started or stopped failing, or its message changed
+    fn update(&mut self, event: &Event) -> bool {
+        let (name, failure) = match event {
+            // INFO: commands failing silently are taken as answers - `grep -c` counting nothing
+            Event::RunCommandResult(exit, _, stderr, context) => {
+                let Some(name) = context.get(poll::POLL) else {
+                    return false;
+                };
+                if EXPECTED.contains(&name.as_str()) {
+                    return false;
+                }
+                let stderr = String::from_utf8_lossy(stderr);
+                let message = stderr.lines().map(str::trim).find(|x| !x.is_empty());
+                let failure = match (exit, message) {
+                    (Some(0), _) | (_, None) => None,
+                    (_, Some(message)) => Some(sanitize::text(message)),
+                };
+                (name.clone(), failure)
+            }
+            // INFO: successful responses are settled once the worker extracted the value
+            Event::WebRequestResult(status, _, _, context) => {
+                let Some(name) = context.get(poll::POLL) else {
+                    return false;
+                };
+                if (200..300).contains(status) {
+                    return false;
+                }
+                (name.clone(), Some(format!("status {status}")))
+            }
+            Event::CustomMessage(name, text) if name == worker::HTTP => {
+                let failure =
+                    Some("no value at `http_path`".to_string()).filter(|_| text.is_empty());
+                (name.clone(), failure)
+            }
+            _ => return false,
+        };
+        match failure {
+            Some(failure) => self.failures.insert(name, failure.clone()) != Some(failure),
+            None => self.failures.remove(&name).is_some(),
+        }
+    }
+
+    fn render(&self, palette: &Palette) -> Segment {
+        if self.failures.is_empty() {
+            return Segment::empty();
+        }
+        let style = AnsiStyle::new()
+            .fg_color(palette.white)
+            .bg_color(palette.red)
+            .bold();
+        if !self.expanded {
+            return Segment::new(Box::new("!"), style, palette).priority(u8::MAX);
+        }
+        let messages: Vec<String> = self
+            .failures
+            .iter()
+            .map(|(name, failure)| format!("{name}: {failure}"))
+            .collect();
+        Segment::new(
+            Box::new(format!("! {}", messages.join(" · "))),
+            style,
+            palette,
+        )
+        .max_width(96)
+        .compact(Box::new("!"))
+        .priority(u8::MAX)
+    }
+}
+
+impl Errors {
+    // INFO: failures of the previous configuration are dropped, the polls report again
+    pub fn new(expanded: bool) -> Self {
+        Self {
+            failures: BTreeMap::new(),
+            expanded,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
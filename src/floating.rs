@@ -0,0 +1,72 @@
+use std::collections::BTreeMap;
+
+use anstyle::Style as AnsiStyle;
+use zellij_tile::prelude::*;
+
+use crate::{glyph, widget::Widget, Palette, Segment};
+
+// NOTE: floating panes of the active tab, clicking toggles their visibility
+#[derive(Default)]
+pub struct Floating {
+    visible: bool,
+    active: Option<usize>,
+    // NOTE: floating panes by tab position, both events may arrive in any order
+    counts: BTreeMap<usize, usize>,
+}
+
+impl Widget for Floating {
+    fn interested_events(&self) -> Vec<EventType> {
+        vec![EventType::TabUpdate, EventType::PaneUpdate]
+    }
+
+    fn update(&mut self, event: &Event) -> bool {
+        let count = self.count();
+        let visible = self.visible;
+        match event {
+            Event::TabUpdate(tabs) => {
+                let active = tabs.iter().find(|x| x.active);
+                self.active = active.map(|x| x.position);
+                self.visible = active.is_some_and(|x| x.are_floating_panes_visible);
+            }
+            Event::PaneUpdate(manifest) => {
+                self.counts = manifest
+                    .panes
+                    .iter()
+                    .map(|(tab, panes)| {
+                        let count = panes
+                            .iter()
+                            .filter(|x| x.is_floating && !x.is_plugin)
+                            .count();
+                        (*tab, count)
+                    })
+                    .collect();
+            }
+            _ => return false,
+        }
+        (count, visible) != (self.count(), self.visible)
+    }
+
+    fn render(&self, palette: &Palette) -> Segment {
+        let count = self.count();
+        let (content, bg) = match (self.visible, count) {
+            (true, _) => (format!("{} {count}", glyph("󰖲", "float")), palette.blue),
+            // INFO: floating panes exist but are hidden
+            (false, 1..) => (format!("{} {count}", glyph("󰖰", "float-")), palette.yellow),
+            (false, 0) => (glyph("󰖲", "float").to_string(), palette.gray),
+        };
+        Segment::new(
+            Box::new(content),
+            AnsiStyle::new().fg_color(palette.black).bg_color(bg),
+            palette,
+        )
+    }
+}
+
+impl Floating {
+    fn count(&self) -> usize {
+        self.active
+            .and_then(|x| self.counts.get(&x))
+            .copied()
+            .unwrap_or(0)
+    }
+}
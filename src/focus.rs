@@ -0,0 +1,121 @@
+use std::{cmp::Reverse, collections::BTreeMap, fs};
+
+use chrono::{DateTime, Datelike, Duration, Local, NaiveTime, Weekday};
+
+use crate::time;
+
+// NOTE: seconds each tab was focused, keyed by ISO week - `2024-W07\teditor\t3600` lines
+const FOCUS_PATH: &str = "/data/focus-time";
+// NOTE: the time of the focused tab is credited at least this often, so little is lost when
+// the session ends
+const FLUSH_SECONDS: i64 = 60;
+// NOTE: ISO week of the last weekly summary shown, so it appears once per week only
+const SUMMARY_MARKER: &str = "/data/weekly-summary-shown";
+
+fn week(date: DateTime<Local>) -> String {
+    let week = date.iso_week();
+    format!("{}-W{:02}", week.year(), week.week())
+}
+
+#[derive(Default)]
+pub struct Focus {
+    weeks: BTreeMap<String, BTreeMap<String, u64>>,
+    active: Option<(String, DateTime<Local>)>,
+    summary_week: String,
+}
+
+impl Focus {
+    pub fn load() -> Self {
+        let mut focus = Self {
+            summary_week: fs::read_to_string(SUMMARY_MARKER)
+                .unwrap_or_default()
+                .trim()
+                .to_string(),
+            ..Self::default()
+        };
+        for line in fs::read_to_string(FOCUS_PATH).unwrap_or_default().lines() {
+            let mut fields = line.split('\t');
+            let (Some(week), Some(tab), Some(Ok(seconds))) =
+                (fields.next(), fields.next(), fields.next().map(str::parse))
+            else {
+                continue;
+            };
+            focus
+                .weeks
+                .entry(week.to_string())
+                .or_default()
+                .insert(tab.to_string(), seconds);
+        }
+        focus
+    }
+
+    // INFO: credit the time since the last switch to the previously active tab - tabs and newlines
+    // of the name would break the lines of the file
+    pub fn switch(&mut self, tab: &str) {
+        let tab = tab.replace(['\t', '\n', '\r'], " ");
+        if self.active.as_ref().is_some_and(|(name, _)| *name == tab) {
+            return;
+        }
+        self.flush(true);
+        self.active = Some((tab, time::now()));
+    }
+
+    // INFO: credits the time since the last switch or flush to the active tab, the week it
+    // started gets all of it
+    pub fn flush(&mut self, force: bool) {
+        let now = time::now();
+        let Some((name, since)) = &mut self.active else {
+            return;
+        };
+        if !force && now - *since < Duration::seconds(FLUSH_SECONDS) {
+            return;
+        }
+        let seconds = (now - *since).num_seconds().max(0) as u64;
+        *self
+            .weeks
+            .entry(week(*since))
+            .or_default()
+            .entry(name.clone())
+            .or_default() += seconds;
+        *since = now;
+        self.save();
+    }
+
+    // INFO: last week's tabs sorted by focused time, once per week after `at` on Monday
+    pub fn weekly_summary(&mut self, at: NaiveTime) -> Option<Vec<(String, u64)>> {
+        let now = time::now();
+        if now.weekday() != Weekday::Mon || now.time() < at {
+            return None;
+        }
+        let current = week(now);
+        if self.summary_week == current {
+            return None;
+        }
+        let _ = fs::write(SUMMARY_MARKER, &current);
+        self.summary_week = current;
+
+        let mut tabs: Vec<(String, u64)> = self
+            .weeks
+            .get(&week(now - Duration::weeks(1)))?
+            .iter()
+            .map(|(name, seconds)| (name.clone(), *seconds))
+            .collect();
+        tabs.sort_by_key(|x| Reverse(x.1));
+        Some(tabs)
+    }
+
+    // INFO: only the current and the previous week are worth keeping
+    fn save(&mut self) {
+        let now = time::now();
+        let keep = [week(now), week(now - Duration::weeks(1))];
+        self.weeks.retain(|week, _| keep.contains(week));
+
+        let mut lines = Vec::new();
+        for (week, tabs) in &self.weeks {
+            for (tab, seconds) in tabs {
+                lines.push(format!("{week}\t{tab}\t{seconds}"));
+            }
+        }
+        let _ = fs::write(FOCUS_PATH, lines.join("\n"));
+    }
+}
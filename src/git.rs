@@ -0,0 +1,76 @@
+use anstyle::Style as AnsiStyle;
+use zellij_tile::prelude::*;
+
+use crate::{memo, poll, sanitize, widget::Widget, Palette, Segment};
+
+pub const BRANCH_COMMAND: &str = "git rev-parse --abbrev-ref HEAD 2>/dev/null";
+pub const WORKTREES_COMMAND: &str = "git worktree list --porcelain";
+
+// NOTE: branch of the repository zellij was started in, empty outside of repositories
+#[derive(Default)]
+pub struct Git {
+    branch: Option<String>,
+    loaded: bool,
+}
+
+impl Widget for Git {
+    fn interested_events(&self) -> Vec<EventType> {
+        vec![EventType::RunCommandResult]
+    }
+
+    // INFO: returns `true` when the branch changed
+    fn update(&mut self, event: &Event) -> bool {
+        let Event::RunCommandResult(_, stdout, _, context) = event else {
+            return false;
+        };
+        if context.get(poll::POLL).map(String::as_str) != Some("git") {
+            return false;
+        }
+        let branch =
+            Some(sanitize::text(String::from_utf8_lossy(stdout).trim())).filter(|x| !x.is_empty());
+        let changed = self.branch != branch || !self.loaded;
+        self.branch = branch;
+        self.loaded = true;
+        changed
+    }
+
+    fn is_loaded(&self) -> bool {
+        self.loaded
+    }
+
+    fn state(&self) -> Option<u64> {
+        Some(memo::hash(&self.branch))
+    }
+
+    fn render(&self, palette: &Palette) -> Segment {
+        let Some(branch) = &self.branch else {
+            return Segment::empty();
+        };
+        Segment::new(
+            Box::new(format!(" {branch}")),
+            AnsiStyle::new()
+                .fg_color(palette.black)
+                .bg_color(palette.magenta),
+            palette,
+        )
+    }
+}
+
+// INFO: `(path, branch)` pairs out of the porcelain format, detached worktrees are named by
+// their directory
+pub fn worktrees(stdout: &[u8]) -> Vec<(String, String)> {
+    let stdout = String::from_utf8_lossy(stdout);
+    let mut worktrees = Vec::new();
+    for block in stdout.split("\n\n") {
+        let Some(path) = block.lines().find_map(|x| x.strip_prefix("worktree ")) else {
+            continue;
+        };
+        let branch = block
+            .lines()
+            .find_map(|x| x.strip_prefix("branch refs/heads/"))
+            .or_else(|| path.rsplit('/').next())
+            .unwrap_or(path);
+        worktrees.push((path.to_string(), sanitize::text(branch)));
+    }
+    worktrees
+}
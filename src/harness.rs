@@ -0,0 +1,136 @@
+use std::collections::BTreeMap;
+
+use insta::assert_snapshot;
+use zellij_tile::prelude::*;
+
+use crate::{config, DisplayExt, PluginState};
+
+// NOTE: import zellij provides to the plugin, the headless one never calls it but the native
+// test binary has to link
+#[no_mangle]
+extern "C" fn host_run_plugin_command() {}
+
+// NOTE: headless plugin fed synthetic zellij events. Frames are snapshotted as plain text so a
+// width miscalculation shows up as shifted or missing columns - `cargo insta review` after an
+// intended change.
+struct Harness {
+    plugin: PluginState,
+}
+
+impl Harness {
+    // INFO: time is pinned, the clock would change every run otherwise
+    fn new(config: &[(&str, &str)]) -> Self {
+        let mut config: BTreeMap<String, String> = config
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+        config
+            .entry("fixed_time".to_string())
+            .or_insert_with(|| "2024-01-01T12:00:00Z".to_string());
+
+        let mut plugin = PluginState {
+            headless: true,
+            ..Default::default()
+        };
+        plugin.widgets = plugin.registry();
+        plugin.config = config::effective(&config);
+        plugin.build();
+        Self { plugin }
+    }
+
+    fn mode(mut self, mode: InputMode) -> Self {
+        self.plugin.update(Event::ModeUpdate(ModeInfo {
+            mode,
+            ..Default::default()
+        }));
+        self
+    }
+
+    fn tabs(mut self, names: &[&str], active: usize) -> Self {
+        let tabs = names
+            .iter()
+            .enumerate()
+            .map(|(position, name)| TabInfo {
+                position,
+                name: name.to_string(),
+                active: position == active,
+                ..Default::default()
+            })
+            .collect();
+        self.plugin.update(Event::TabUpdate(tabs));
+        self
+    }
+
+    fn session(mut self, name: &str) -> Self {
+        let session = SessionInfo {
+            name: name.to_string(),
+            is_current_session: true,
+            connected_clients: 1,
+            ..Default::default()
+        };
+        self.plugin
+            .update(Event::SessionUpdate(vec![session], Vec::new()));
+        self
+    }
+
+    // INFO: one line per width, each checked against the columns it was given
+    fn render(&mut self, widths: &[usize]) -> String {
+        let mut lines = Vec::new();
+        for cols in widths {
+            self.plugin.render_frame(1, *cols);
+            let line = strip_ansi_escapes::strip_str(&self.plugin.frame);
+            assert!(
+                line.display_len() <= *cols,
+                "{} cells in {cols} columns: {line}",
+                line.display_len()
+            );
+            lines.push(format!("{cols:>3} |{line}|"));
+        }
+        lines.join("\n")
+    }
+}
+
+#[test]
+fn normal_mode_with_tabs() {
+    let mut harness = Harness::new(&[])
+        .session("docs")
+        .mode(InputMode::Normal)
+        .tabs(&["editor", "logs", "build"], 1);
+    assert_snapshot!(harness.render(&[60, 80, 120, 200]));
+}
+
+#[test]
+fn tabs_fold_in_narrow_panes() {
+    let mut harness = Harness::new(&[])
+        .session("a-rather-long-session-name")
+        .mode(InputMode::Locked)
+        .tabs(&["one", "two", "three", "four", "five", "six", "seven"], 3);
+    assert_snapshot!(harness.render(&[30, 50, 70]));
+}
+
+#[test]
+fn wide_glyphs_in_tab_names() {
+    let mut harness = Harness::new(&[("nerd_fonts", "false")])
+        .session("ワークスペース")
+        .mode(InputMode::Pane)
+        .tabs(&["日本語", "emoji 🦀", "e\u{301}dition"], 0);
+    assert_snapshot!(harness.render(&[40, 80]));
+}
+
+#[test]
+fn scroll_mode_notice() {
+    let mut harness = Harness::new(&[("layout", "mode session tabs | clock")])
+        .session("docs")
+        .tabs(&["editor"], 0)
+        .mode(InputMode::Scroll);
+    assert_snapshot!(harness.render(&[80]));
+}
+
+#[test]
+fn elements_around_tabs_keep_their_own_edges() {
+    let mut harness = Harness::new(&[("layout", "mode tabs session")])
+        .session("docs")
+        .mode(InputMode::Normal)
+        .tabs(&["editor", "logs"], 0);
+    assert_snapshot!(harness.render(&[80]));
+}
@@ -0,0 +1,41 @@
+use std::{collections::BTreeSet, fs};
+
+// NOTE: segments hidden with `statusline::toggle`, persisted across plugin reloads
+const HIDDEN_PATH: &str = "/data/hidden-segments";
+
+#[derive(Default)]
+pub struct Hidden {
+    names: BTreeSet<String>,
+}
+
+impl Hidden {
+    pub fn load() -> Self {
+        let names = fs::read_to_string(HIDDEN_PATH)
+            .unwrap_or_default()
+            .lines()
+            .map(str::trim)
+            .filter(|x| !x.is_empty())
+            .map(String::from)
+            .collect();
+        Self { names }
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.names.contains(name)
+    }
+
+    pub fn toggle(&mut self, name: &str) {
+        let hidden = !self.contains(name);
+        self.set(name, hidden);
+    }
+
+    pub fn set(&mut self, name: &str, hidden: bool) {
+        if hidden {
+            self.names.insert(name.to_string());
+        } else {
+            self.names.remove(name);
+        }
+        let names: Vec<&str> = self.names.iter().map(String::as_str).collect();
+        let _ = fs::write(HIDDEN_PATH, names.join("\n"));
+    }
+}
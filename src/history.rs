@@ -0,0 +1,76 @@
+use std::collections::VecDeque;
+
+use anstyle::Style as AnsiStyle;
+use chrono::{DateTime, Local};
+use zellij_tile::prelude::*;
+
+use crate::{time, Palette, Segment};
+
+// NOTE: recently piped notifications, paged through in a transient line so a missed one can
+// still be read
+#[derive(Default)]
+pub struct History {
+    entries: VecDeque<(DateTime<Local>, String, String)>,
+    pub capacity: usize,
+    page: Option<usize>,
+}
+
+impl History {
+    pub fn render(&self, palette: &Palette) -> String {
+        let Some(page) = self.page else {
+            return String::new();
+        };
+        let content = match self.entries.get(page) {
+            Some((at, name, text)) => format!(
+                "{}/{}  {}  {name}: {text}  ◀ ▶",
+                self.entries.len() - page,
+                self.entries.len(),
+                at.format("%H:%M:%S"),
+            ),
+            None => "0/0".to_string(),
+        };
+        Segment::new(
+            Box::new(content),
+            AnsiStyle::new()
+                .fg_color(palette.black)
+                .bg_color(palette.cyan),
+            palette,
+        )
+        .max_width(256)
+        .to_string()
+    }
+
+    pub fn push(&mut self, name: &str, text: &str) {
+        self.entries
+            .push_front((time::now(), name.to_string(), text.to_string()));
+        self.entries.truncate(self.capacity);
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.page.is_some()
+    }
+
+    // INFO: starts at the latest notification
+    pub fn open(&mut self) {
+        self.page = Some(0);
+    }
+
+    // INFO: left goes back in time, clicks too, right-click or escape closes the viewer
+    pub fn handle(&mut self, event: &Event) -> bool {
+        let Some(page) = self.page else {
+            return false;
+        };
+        let last = self.entries.len().saturating_sub(1);
+        self.page = match event {
+            Event::Key(Key::Left) | Event::Mouse(Mouse::LeftClick(..) | Mouse::ScrollDown(_)) => {
+                Some((page + 1).min(last))
+            }
+            Event::Key(Key::Right) | Event::Mouse(Mouse::ScrollUp(_)) => {
+                Some(page.saturating_sub(1))
+            }
+            Event::Key(Key::Esc) | Event::Mouse(Mouse::RightClick(..)) => None,
+            _ => return false,
+        };
+        true
+    }
+}
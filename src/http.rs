@@ -0,0 +1,94 @@
+use std::collections::BTreeMap;
+
+use anstyle::{Color, Style as AnsiStyle};
+use zellij_tile::prelude::*;
+
+use crate::{
+    parse_color, poll,
+    widget::Widget,
+    worker::{self, HttpJob},
+    Palette, Segment,
+};
+
+// NOTE: value extracted from a polled JSON endpoint - `http_path = ".data.status"`
+#[derive(Default)]
+pub struct Http {
+    path: String,
+    colors: BTreeMap<String, Color>,
+    text: Option<String>,
+}
+
+impl Widget for Http {
+    fn interested_events(&self) -> Vec<EventType> {
+        vec![EventType::WebRequestResult, EventType::CustomMessage]
+    }
+
+    // INFO: bodies are handed to the worker, returns `true` when the value it extracted changed,
+    // failed requests show `?`
+    fn update(&mut self, event: &Event) -> bool {
+        let text = match event {
+            Event::WebRequestResult(status, _, body, context)
+                if context.get(poll::POLL).map(String::as_str) == Some("http") =>
+            {
+                if !(200..300).contains(status) {
+                    None
+                } else {
+                    let job = HttpJob {
+                        path: self.path.clone(),
+                        body: String::from_utf8_lossy(body).into_owned(),
+                    };
+                    if let Ok(job) = serde_json::to_string(&job) {
+                        worker::post(worker::HTTP, &job);
+                    }
+                    return false;
+                }
+            }
+            Event::CustomMessage(name, text) if name == worker::HTTP => {
+                Some(text.clone()).filter(|x| !x.is_empty())
+            }
+            _ => return false,
+        };
+        let text = Some(text.unwrap_or_else(|| "?".to_string()));
+        let changed = self.text != text;
+        self.text = text;
+        changed
+    }
+
+    fn is_loaded(&self) -> bool {
+        self.text.is_some()
+    }
+
+    fn render(&self, palette: &Palette) -> Segment {
+        let Some(text) = &self.text else {
+            return Segment::empty();
+        };
+        let bg = self.colors.get(text).copied().or(palette.cyan);
+        Segment::new(
+            Box::new(text.clone()),
+            AnsiStyle::new().fg_color(palette.black).bg_color(bg),
+            palette,
+        )
+    }
+}
+
+impl Http {
+    // INFO: `colors` maps extracted values to colors - `ok:green,degraded:yellow,down:red`,
+    // invalid colors are left out for the default one
+    pub fn new(path: Option<&String>, colors: Option<&String>) -> Self {
+        let colors = colors
+            .map(|x| {
+                x.split(',')
+                    .filter_map(|x| x.split_once(':'))
+                    .filter_map(|(value, color)| {
+                        Some((value.trim().to_string(), parse_color(color)?))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self {
+            path: path.cloned().unwrap_or_default(),
+            colors,
+            text: None,
+        }
+    }
+}
@@ -0,0 +1,215 @@
+use serde::{
+    de::{value, IntoDeserializer},
+    Deserialize,
+};
+
+use crate::{config::Config, i18n, numerals::Numerals};
+
+#[derive(Default, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Units {
+    // INFO: powers of 1000 - kB, MB, GB
+    #[default]
+    Si,
+    // INFO: powers of 1024 - KiB, MiB, GiB
+    Iec,
+}
+
+#[derive(Default, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DurationStyle {
+    // INFO: `2h13m`
+    #[default]
+    Compact,
+    // INFO: `2:13:05`
+    Colon,
+    // INFO: `about 2 hours`
+    Fuzzy,
+}
+
+#[derive(Clone, Copy)]
+pub struct HumanFormat {
+    units: Units,
+    decimal_separator: char,
+    duration_style: DurationStyle,
+    numerals: Numerals,
+}
+
+impl Default for HumanFormat {
+    fn default() -> Self {
+        Self {
+            units: Units::Si,
+            decimal_separator: '.',
+            duration_style: DurationStyle::Compact,
+            numerals: Numerals::default(),
+        }
+    }
+}
+
+impl HumanFormat {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            units: config.units,
+            decimal_separator: config.decimal_separator,
+            duration_style: config.duration_style,
+            numerals: config.numeral_locale,
+        }
+    }
+
+    // INFO: `kind` is one of `bytes`, `rate`, `duration` or `number`, durations may override
+    // the configured style - `duration:colon`
+    pub fn format(&self, kind: &str, value: f64) -> Option<String> {
+        match kind.split_once(':') {
+            Some(("duration", style)) => {
+                let style: Result<DurationStyle, value::Error> =
+                    DurationStyle::deserialize(style.into_deserializer());
+                Some(self.duration_as(value, style.ok()?))
+            }
+            Some(_) => None,
+            None => match kind {
+                "bytes" => Some(self.bytes(value)),
+                "rate" => Some(self.rate(value)),
+                "duration" => Some(self.duration(value)),
+                "number" => Some(self.number(value)),
+                _ => None,
+            },
+        }
+    }
+
+    pub fn bytes(&self, value: f64) -> String {
+        let (base, units): (f64, &[&str]) = match self.units {
+            Units::Si => (1000.0, &["B", "kB", "MB", "GB", "TB", "PB"]),
+            Units::Iec => (1024.0, &["B", "KiB", "MiB", "GiB", "TiB", "PiB"]),
+        };
+        self.scaled(value, base, units, " ")
+    }
+
+    pub fn rate(&self, value: f64) -> String {
+        format!("{}/s", self.bytes(value))
+    }
+
+    pub fn number(&self, value: f64) -> String {
+        self.scaled(value, 1000.0, &["", "k", "M", "G", "T"], "")
+    }
+
+    pub fn duration(&self, seconds: f64) -> String {
+        self.duration_as(seconds, self.duration_style)
+    }
+
+    pub fn duration_as(&self, seconds: f64, style: DurationStyle) -> String {
+        let seconds = seconds.max(0.0) as u64;
+        let duration = match style {
+            DurationStyle::Compact => Self::compact(seconds),
+            DurationStyle::Colon => Self::colon(seconds),
+            DurationStyle::Fuzzy => Self::fuzzy(seconds),
+        };
+        self.numerals.digits(&duration)
+    }
+
+    // INFO: largest two units only - `2h13m`, `3d4h`, `45s`
+    fn compact(seconds: u64) -> String {
+        let parts = [
+            (seconds / 86400, "d"),
+            (seconds % 86400 / 3600, "h"),
+            (seconds % 3600 / 60, "m"),
+            (seconds % 60, "s"),
+        ];
+        let first = parts.iter().position(|(x, _)| *x > 0).unwrap_or(3);
+        parts[first..]
+            .iter()
+            .take(2)
+            .filter(|(x, _)| *x > 0 || first == 3)
+            .map(|(x, unit)| format!("{x}{unit}"))
+            .collect()
+    }
+
+    // INFO: hours aren't wrapped into days - `2:13:05`, `50:00:00`, `0:45`
+    fn colon(seconds: u64) -> String {
+        let (hours, minutes, seconds) = (seconds / 3600, seconds % 3600 / 60, seconds % 60);
+        if hours > 0 {
+            format!("{hours}:{minutes:02}:{seconds:02}")
+        } else {
+            format!("{minutes}:{seconds:02}")
+        }
+    }
+
+    // INFO: rounded to the nearest largest unit - `about 2 hours`, `less than a minute`
+    fn fuzzy(seconds: u64) -> String {
+        if seconds < 60 {
+            return i18n::tr("fuzzy_less_than_minute");
+        }
+        let (count, unit) = [(86400, "day"), (3600, "hour"), (60, "minute")]
+            .into_iter()
+            .find(|(size, _)| seconds >= *size)
+            .map(|(size, unit)| ((seconds + size / 2) / size, unit))
+            .unwrap_or((1, "minute"));
+        match count {
+            1 => i18n::tr(&format!("fuzzy_{unit}")),
+            _ => i18n::tr(&format!("fuzzy_{unit}s")).replace("{count}", &count.to_string()),
+        }
+    }
+
+    fn scaled(&self, value: f64, base: f64, units: &[&str], space: &str) -> String {
+        let mut value = value;
+        let mut unit = 0;
+        while value.abs() >= base && unit < units.len() - 1 {
+            value /= base;
+            unit += 1;
+        }
+
+        // INFO: one decimal place for small values only - `1.5 GB`, `15 GB`
+        let number = if unit > 0 && value.abs() < 10.0 {
+            format!("{value:.1}")
+        } else {
+            format!("{value:.0}")
+        };
+        let number = number.replace('.', &self.decimal_separator.to_string());
+        let number = self.numerals.format(&number);
+        format!("{number}{space}{}", units[unit])
+            .trim_end()
+            .to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scales_bytes_and_rates() {
+        let human = HumanFormat::default();
+        assert_eq!(human.bytes(999.0), "999 B");
+        assert_eq!(human.bytes(1500.0), "1.5 kB");
+        assert_eq!(human.bytes(15_000_000.0), "15 MB");
+        assert_eq!(human.rate(2048.0), "2.0 kB/s");
+        let iec = HumanFormat {
+            units: Units::Iec,
+            decimal_separator: ',',
+            ..HumanFormat::default()
+        };
+        assert_eq!(iec.bytes(1536.0), "1,5 KiB");
+    }
+
+    #[test]
+    fn formats_durations_in_each_style() {
+        let human = HumanFormat::default();
+        assert_eq!(human.duration(45.0), "45s");
+        assert_eq!(human.duration(7985.0), "2h13m");
+        assert_eq!(human.duration(90000.0), "1d1h");
+        assert_eq!(human.format("duration:colon", 7985.0).unwrap(), "2:13:05");
+        assert_eq!(human.format("duration:colon", 45.0).unwrap(), "0:45");
+        assert_eq!(human.format("duration:sideways", 45.0), None);
+    }
+
+    #[test]
+    fn rounds_fuzzy_durations() {
+        let human = HumanFormat::default();
+        let fuzzy = |x| human.format("duration:fuzzy", x).unwrap();
+        assert_eq!(fuzzy(59.0), "less than a minute");
+        assert_eq!(fuzzy(60.0), "about a minute");
+        assert_eq!(fuzzy(150.0), "about 3 minutes");
+        assert_eq!(fuzzy(3600.0), "about an hour");
+        assert_eq!(fuzzy(9000.0), "about 3 hours");
+        assert_eq!(fuzzy(86400.0 * 2.4), "about 2 days");
+    }
+}
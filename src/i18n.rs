@@ -0,0 +1,353 @@
+use std::{cell::RefCell, collections::BTreeMap};
+
+// NOTE: prefix of configuration keys overriding single labels - `label_mode_normal = "NORMALNY"`
+const LABEL_PREFIX: &str = "label_";
+
+type Table = &'static [(&'static str, &'static str)];
+
+const EN: Table = &[
+    ("mode_normal", "NORMAL"),
+    ("mode_locked", "LOCKED"),
+    ("mode_resize", "RESIZE"),
+    ("mode_pane", "PANE"),
+    ("mode_tab", "TAB"),
+    ("mode_scroll", "SCROLL"),
+    ("mode_search", "SEARCH"),
+    ("mode_session", "SESSION"),
+    ("mode_move", "MOVE"),
+    ("mode_prompt", "PROMPT"),
+    ("mode_tmux", "TMUX"),
+    ("actions", "ACTIONS"),
+    ("new_tab", "new tab"),
+    ("detach", "detach"),
+    ("lock", "lock"),
+    ("zen", "zen"),
+    ("close_tab", "close tab"),
+    ("clear_notifications", "clear notifications"),
+    ("quit", "quit"),
+    ("confirm_close_tab", "close tab {tab}?"),
+    ("confirm_clear_notifications", "clear notifications?"),
+    ("confirm_quit", "quit session?"),
+    ("click_again", "click again within {seconds}s"),
+    ("setup", "SETUP"),
+    (
+        "setup_hint",
+        "(click/arrows: change, right click/enter: next, esc: skip)",
+    ),
+    ("heading_mode", "MODE"),
+    ("heading_session", "SESSION"),
+    ("heading_tabs", "TABS"),
+    ("starting", "starting…"),
+    ("grant_permission", "grant permission to enable statusline"),
+    ("weekly_summary", "last week:"),
+    ("battery_critical", "battery critical {percent}%"),
+    ("worktrees", "worktrees"),
+    ("viewing_history", "VIEWING HISTORY"),
+    (
+        "config_file_error",
+        "Configuration file not loaded - {error}",
+    ),
+    ("theme_file_error", "Theme file not loaded - {error}"),
+    ("config_dump_error", "Configuration not written - {error}"),
+    ("order_changed", "configure {setting} to keep this order"),
+    ("client_attached", "client attached, {count} connected"),
+    ("client_detached", "client detached, {count} connected"),
+    ("fuzzy_less_than_minute", "less than a minute"),
+    ("fuzzy_minute", "about a minute"),
+    ("fuzzy_minutes", "about {count} minutes"),
+    ("fuzzy_hour", "about an hour"),
+    ("fuzzy_hours", "about {count} hours"),
+    ("fuzzy_day", "about a day"),
+    ("fuzzy_days", "about {count} days"),
+    ("usage_today", "today: {totals}"),
+    ("usage_week", "week: {totals}"),
+    (
+        "usage_disabled",
+        "mode usage isn't tracked, set `mode_usage = true`",
+    ),
+];
+
+const DE: Table = &[
+    ("mode_normal", "NORMAL"),
+    ("mode_locked", "GESPERRT"),
+    ("mode_resize", "GRÖSSE"),
+    ("mode_pane", "BEREICH"),
+    ("mode_tab", "TAB"),
+    ("mode_scroll", "SCROLLEN"),
+    ("mode_search", "SUCHE"),
+    ("mode_session", "SITZUNG"),
+    ("mode_move", "VERSCHIEBEN"),
+    ("mode_prompt", "EINGABE"),
+    ("mode_tmux", "TMUX"),
+    ("actions", "AKTIONEN"),
+    ("new_tab", "neuer Tab"),
+    ("detach", "trennen"),
+    ("lock", "sperren"),
+    ("zen", "Zen"),
+    ("close_tab", "Tab schließen"),
+    ("clear_notifications", "Benachrichtigungen löschen"),
+    ("quit", "beenden"),
+    ("confirm_close_tab", "Tab {tab} schließen?"),
+    ("confirm_clear_notifications", "Benachrichtigungen löschen?"),
+    ("confirm_quit", "Sitzung beenden?"),
+    ("click_again", "innerhalb von {seconds}s erneut klicken"),
+    ("setup", "EINRICHTUNG"),
+    (
+        "setup_hint",
+        "(Klick/Pfeile: ändern, Rechtsklick/Enter: weiter, Esc: überspringen)",
+    ),
+    ("heading_mode", "MODUS"),
+    ("heading_session", "SITZUNG"),
+    ("heading_tabs", "TABS"),
+    ("starting", "startet…"),
+    (
+        "grant_permission",
+        "Berechtigung erteilen, um die Statuszeile zu aktivieren",
+    ),
+    ("weekly_summary", "letzte Woche:"),
+    ("battery_critical", "Akku kritisch {percent}%"),
+    ("worktrees", "Worktrees"),
+    ("viewing_history", "VERLAUF ANSICHT"),
+    (
+        "config_file_error",
+        "Konfigurationsdatei nicht geladen - {error}",
+    ),
+    ("theme_file_error", "Themendatei nicht geladen - {error}"),
+    (
+        "config_dump_error",
+        "Konfiguration nicht geschrieben - {error}",
+    ),
+    (
+        "order_changed",
+        "{setting} konfigurieren, um diese Reihenfolge zu behalten",
+    ),
+    ("client_attached", "Client verbunden, {count} verbunden"),
+    ("client_detached", "Client getrennt, {count} verbunden"),
+    ("fuzzy_less_than_minute", "weniger als eine Minute"),
+    ("fuzzy_minute", "etwa eine Minute"),
+    ("fuzzy_minutes", "etwa {count} Minuten"),
+    ("fuzzy_hour", "etwa eine Stunde"),
+    ("fuzzy_hours", "etwa {count} Stunden"),
+    ("fuzzy_day", "etwa ein Tag"),
+    ("fuzzy_days", "etwa {count} Tage"),
+    ("usage_today", "heute: {totals}"),
+    ("usage_week", "Woche: {totals}"),
+    (
+        "usage_disabled",
+        "Modusnutzung wird nicht erfasst, `mode_usage = true` setzen",
+    ),
+];
+
+const PL: Table = &[
+    ("mode_normal", "NORMALNY"),
+    ("mode_locked", "ZABLOKOWANY"),
+    ("mode_resize", "ROZMIAR"),
+    ("mode_pane", "PANEL"),
+    ("mode_tab", "KARTA"),
+    ("mode_scroll", "PRZEWIJANIE"),
+    ("mode_search", "SZUKAJ"),
+    ("mode_session", "SESJA"),
+    ("mode_move", "PRZENIEŚ"),
+    ("mode_prompt", "MONIT"),
+    ("mode_tmux", "TMUX"),
+    ("actions", "AKCJE"),
+    ("new_tab", "nowa karta"),
+    ("detach", "odłącz"),
+    ("lock", "zablokuj"),
+    ("zen", "zen"),
+    ("close_tab", "zamknij kartę"),
+    ("clear_notifications", "wyczyść powiadomienia"),
+    ("quit", "zakończ"),
+    ("confirm_close_tab", "zamknąć kartę {tab}?"),
+    ("confirm_clear_notifications", "wyczyścić powiadomienia?"),
+    ("confirm_quit", "zakończyć sesję?"),
+    ("click_again", "kliknij ponownie w ciągu {seconds}s"),
+    ("setup", "KONFIGURACJA"),
+    (
+        "setup_hint",
+        "(klik/strzałki: zmień, prawy klik/enter: dalej, esc: pomiń)",
+    ),
+    ("heading_mode", "TRYB"),
+    ("heading_session", "SESJA"),
+    ("heading_tabs", "KARTY"),
+    ("starting", "uruchamianie…"),
+    (
+        "grant_permission",
+        "nadaj uprawnienia, aby włączyć pasek stanu",
+    ),
+    ("weekly_summary", "zeszły tydzień:"),
+    ("battery_critical", "krytyczny poziom baterii {percent}%"),
+    ("worktrees", "drzewa robocze"),
+    ("viewing_history", "PRZEGLĄDANIE HISTORII"),
+    (
+        "config_file_error",
+        "Nie wczytano pliku konfiguracji - {error}",
+    ),
+    ("theme_file_error", "Nie wczytano pliku motywu - {error}"),
+    ("config_dump_error", "Nie zapisano konfiguracji - {error}"),
+    (
+        "order_changed",
+        "ustaw {setting}, aby zachować tę kolejność",
+    ),
+    ("client_attached", "klient dołączył, połączonych: {count}"),
+    (
+        "client_detached",
+        "klient odłączył się, połączonych: {count}",
+    ),
+    ("fuzzy_less_than_minute", "mniej niż minuta"),
+    ("fuzzy_minute", "około minuty"),
+    ("fuzzy_minutes", "około {count} min"),
+    ("fuzzy_hour", "około godziny"),
+    ("fuzzy_hours", "około {count} godz."),
+    ("fuzzy_day", "około dnia"),
+    ("fuzzy_days", "około {count} dni"),
+    ("usage_today", "dziś: {totals}"),
+    ("usage_week", "tydzień: {totals}"),
+    (
+        "usage_disabled",
+        "czas w trybach nie jest mierzony, ustaw `mode_usage = true`",
+    ),
+];
+
+const ES: Table = &[
+    ("mode_normal", "NORMAL"),
+    ("mode_locked", "BLOQUEADO"),
+    ("mode_resize", "TAMAÑO"),
+    ("mode_pane", "PANEL"),
+    ("mode_tab", "PESTAÑA"),
+    ("mode_scroll", "DESPLAZAR"),
+    ("mode_search", "BUSCAR"),
+    ("mode_session", "SESIÓN"),
+    ("mode_move", "MOVER"),
+    ("mode_prompt", "ENTRADA"),
+    ("mode_tmux", "TMUX"),
+    ("actions", "ACCIONES"),
+    ("new_tab", "nueva pestaña"),
+    ("detach", "desconectar"),
+    ("lock", "bloquear"),
+    ("zen", "zen"),
+    ("close_tab", "cerrar pestaña"),
+    ("clear_notifications", "borrar notificaciones"),
+    ("quit", "salir"),
+    ("confirm_close_tab", "¿cerrar pestaña {tab}?"),
+    ("confirm_clear_notifications", "¿borrar notificaciones?"),
+    ("confirm_quit", "¿salir de la sesión?"),
+    ("click_again", "haz clic de nuevo en {seconds}s"),
+    ("setup", "CONFIGURACIÓN"),
+    (
+        "setup_hint",
+        "(clic/flechas: cambiar, clic derecho/enter: siguiente, esc: omitir)",
+    ),
+    ("heading_mode", "MODO"),
+    ("heading_session", "SESIÓN"),
+    ("heading_tabs", "PESTAÑAS"),
+    ("starting", "iniciando…"),
+    (
+        "grant_permission",
+        "concede permisos para activar la barra de estado",
+    ),
+    ("weekly_summary", "la semana pasada:"),
+    ("battery_critical", "batería crítica {percent}%"),
+    ("worktrees", "árboles de trabajo"),
+    ("viewing_history", "VIENDO HISTORIAL"),
+    (
+        "config_file_error",
+        "Archivo de configuración no cargado - {error}",
+    ),
+    ("theme_file_error", "Archivo de tema no cargado - {error}"),
+    ("config_dump_error", "Configuración no guardada - {error}"),
+    (
+        "order_changed",
+        "configura {setting} para mantener este orden",
+    ),
+    ("client_attached", "cliente conectado, {count} conectados"),
+    (
+        "client_detached",
+        "cliente desconectado, {count} conectados",
+    ),
+    ("fuzzy_less_than_minute", "menos de un minuto"),
+    ("fuzzy_minute", "cerca de un minuto"),
+    ("fuzzy_minutes", "cerca de {count} minutos"),
+    ("fuzzy_hour", "cerca de una hora"),
+    ("fuzzy_hours", "cerca de {count} horas"),
+    ("fuzzy_day", "cerca de un día"),
+    ("fuzzy_days", "cerca de {count} días"),
+    ("usage_today", "hoy: {totals}"),
+    ("usage_week", "semana: {totals}"),
+    (
+        "usage_disabled",
+        "el uso de modos no se registra, configura `mode_usage = true`",
+    ),
+];
+
+const LANGUAGES: &[(&str, Table)] = &[("en", EN), ("de", DE), ("pl", PL), ("es", ES)];
+
+thread_local! {
+    // NOTE: labels of the selected `lang` with per label overrides from the configuration
+    static LABELS: RefCell<BTreeMap<&'static str, String>> = RefCell::new(labels("en", &BTreeMap::new()));
+}
+
+fn labels(lang: &str, config: &BTreeMap<String, String>) -> BTreeMap<&'static str, String> {
+    let table = LANGUAGES
+        .iter()
+        .find(|(name, _)| *name == lang)
+        .map_or(EN, |(_, table)| *table);
+
+    EN.iter()
+        .map(|(key, english)| {
+            let label = config
+                .get(&format!("{LABEL_PREFIX}{key}"))
+                .cloned()
+                .or_else(|| {
+                    table
+                        .iter()
+                        .find(|(x, _)| x == key)
+                        .map(|(_, x)| x.to_string())
+                })
+                .unwrap_or_else(|| english.to_string());
+            (*key, label)
+        })
+        .collect()
+}
+
+pub fn set_language(lang: &str, config: &BTreeMap<String, String>) {
+    LABELS.with(|x| *x.borrow_mut() = labels(lang, config));
+}
+
+pub fn tr(key: &str) -> String {
+    LABELS.with(|x| {
+        x.borrow()
+            .get(key)
+            .cloned()
+            .unwrap_or_else(|| key.to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_language_translates_every_key() {
+        for (lang, table) in LANGUAGES {
+            for (key, _) in EN {
+                assert!(table.iter().any(|(x, _)| x == key), "{lang} misses `{key}`");
+            }
+            assert_eq!(table.len(), EN.len(), "{lang} has keys english doesn't");
+        }
+    }
+
+    #[test]
+    fn overrides_win_over_the_language() {
+        let config = BTreeMap::from([
+            ("label_mode_locked".to_string(), "ZU".to_string()),
+            ("label_heading_mode".to_string(), "M".to_string()),
+        ]);
+        let german = labels("de", &config);
+        assert_eq!(german["mode_locked"], "ZU");
+        assert_eq!(german["heading_mode"], "M");
+        assert_eq!(german["mode_normal"], "NORMAL");
+        assert_eq!(german["mode_scroll"], "SCROLLEN");
+        assert_eq!(labels("xx", &BTreeMap::new())["mode_scroll"], "SCROLL");
+    }
+}
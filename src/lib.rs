@@ -0,0 +1,86 @@
+pub mod budget;
+pub mod caps;
+pub mod clock;
+pub mod memo;
+pub mod numerals;
+pub mod palette;
+pub mod render;
+pub mod sanitize;
+pub mod segment;
+pub mod statusline;
+pub mod tabs;
+pub mod time;
+pub mod widget;
+
+use std::fmt::Display;
+
+use anstyle::Style as AnsiStyle;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+pub use palette::*;
+pub use render::RenderWidth;
+pub use segment::*;
+pub use statusline::Statusline;
+
+pub trait DisplayExt {
+    fn display_len(&self) -> usize;
+}
+
+impl<T: Display> DisplayExt for T {
+    fn display_len(&self) -> usize {
+        width(&strip_ansi_escapes::strip_str(self.to_string()))
+    }
+}
+
+// NOTE: terminal cells of a grapheme cluster - wide CJK and emoji take two, ZWJ sequences and
+// flags never more than that however many code points they join
+pub fn grapheme_width(grapheme: &str) -> usize {
+    grapheme.width().min(2)
+}
+
+pub fn width(text: &str) -> usize {
+    text.graphemes(true).map(grapheme_width).sum()
+}
+
+// INFO: longest run of whole clusters at the end of `text` fitting `cells`, along with its width
+pub fn truncate_start(text: &str, cells: usize) -> (String, usize) {
+    let mut kept = Vec::new();
+    let mut used = 0;
+    for grapheme in text.graphemes(true).rev() {
+        let width = grapheme_width(grapheme);
+        if used + width > cells {
+            break;
+        }
+        kept.push(grapheme);
+        used += width;
+    }
+    (kept.into_iter().rev().collect(), used)
+}
+
+// INFO: longest run of whole clusters fitting `cells`, along with its width
+pub fn truncate(text: &str, cells: usize) -> (String, usize) {
+    let mut truncated = String::new();
+    let mut used = 0;
+    for grapheme in text.graphemes(true) {
+        let width = grapheme_width(grapheme);
+        if used + width > cells {
+            break;
+        }
+        truncated.push_str(grapheme);
+        used += width;
+    }
+    (truncated, used)
+}
+
+pub fn fill(width: usize, pattern: &str, palette: &Palette) -> String {
+    fill_styled(
+        width,
+        pattern,
+        AnsiStyle::new().fg_color(palette.gray).bg_color(palette.bg),
+    )
+}
+
+pub fn fill_styled(width: usize, pattern: &str, style: AnsiStyle) -> String {
+    format!("{}{}", style.render(), pattern.repeat(width))
+}
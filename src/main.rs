@@ -1,181 +1,2150 @@
-mod clock;
-mod segment;
-mod tabs;
+mod accessible;
+mod auth;
+mod battery;
+mod breakpoints;
+mod buttons;
+mod cmd;
+mod compat;
+mod config;
+mod config_file;
+mod confirm;
+mod contrast;
+mod custom;
+mod debug;
+mod diagnostics;
+mod environment;
+mod errors;
+mod floating;
+mod focus;
+mod git;
+#[cfg(test)]
+mod harness;
+mod hidden;
+mod history;
+mod http;
+mod human;
+mod i18n;
+mod menu;
+mod panes;
+mod pipe;
+mod placeholders;
+mod poll;
+mod power;
+mod preview;
+mod profiles;
+mod recorder;
+mod rules;
+mod script;
+mod sparkline;
+mod text;
+mod theme;
+mod updates;
+mod usage;
+mod visibility;
+mod wizard;
+mod worker;
+mod workspace;
 
+use accessible::Plain;
+use auth::Auth;
+use battery::Battery;
+use breakpoints::Breakpoints;
+use buttons::Buttons;
 use clock::Clock;
+use cmd::CmdDuration;
+use config::{Config, HAlign, LowPower, Scope, ToastArea, VAlign};
+use config_file::ConfigFile;
+use confirm::Confirm;
+use custom::Custom;
+use diagnostics::Diagnostics;
+use environment::Environment;
+use errors::Errors;
+use floating::Floating;
+use focus::Focus;
+use git::Git;
+use hidden::Hidden;
+use history::History;
+use http::Http;
+use human::HumanFormat;
+use kdl::{KdlDocument, KdlNode};
+use memo::Memo;
+use menu::{Menu, Selection};
+use palette::Palette;
+use panes::Panes;
+use pipe::Request;
+use placeholders::Placeholders;
+use poll::Poll;
+use power::Power;
+use profiles::Profiles;
+use recorder::{Entry, Recorder};
+use rules::Rules;
+use script::Script;
 use segment::*;
 use tabs::Tabs;
+use text::Text;
+use theme::Theme;
+use updates::Updates;
+use usage::Usage;
+use visibility::{Facts, Visibility};
+use widget::Widget;
+use wizard::Wizard;
+use worker::Worker;
+use workspace::Workspace;
+use zellij_statusline_core::{
+    budget, caps, clock, fill, memo, numerals, palette, sanitize, segment, statusline, tabs, time,
+    truncate, widget, DisplayExt, RenderWidth, Statusline,
+};
 use zellij_tile::prelude::*;
 
-use std::{cell::RefCell, collections::BTreeMap, fmt::Display, ops::Deref, rc::Rc};
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet},
+    fmt::{Display, Write as _},
+    ops::{Deref, Range},
+    rc::Rc,
+};
 
 use anstyle::{Color, Style as AnsiStyle};
-use unicode_segmentation::UnicodeSegmentation;
 
 #[derive(Default)]
 pub struct PluginState {
+    // NOTE: keys of the plugin block as zellij passed them, kept for reloads
+    pub plugin_config: BTreeMap<String, String>,
     pub config: BTreeMap<String, String>,
+    pub settings: Config,
+    pub diagnostics: Diagnostics,
+    pub errors: Errors,
+    pub theme: Theme,
+    // NOTE: segments reused until the state of their widget changes
+    pub memo: Memo,
+    // NOTE: palette of the zellij theme once `use_zellij_theme` received it
+    pub zellij_palette: Option<palette::Palette>,
+    // NOTE: colors the elements are rendered with, resolved from the theme on every reload
+    pub palette: Palette,
+    // NOTE: desktop appearance reported by `appearance_command`, `None` until it first ran
+    pub dark: Option<bool>,
 
-    pub mode: Shared<String>,
-    pub session: Shared<String>,
-    pub tabs: Tabs,
+    pub mode: Shared<Mode>,
+    pub session: Shared<Session>,
+    pub custom: Shared<Custom>,
+    pub context: Shared<Context>,
+    pub workspace: Shared<Workspace>,
+    pub battery: Shared<Battery>,
+    pub power: Shared<Power>,
+    // NOTE: power source the configuration was last built for
+    pub on_battery: bool,
+    // NOTE: longer intervals, no seconds and no blinking - `low_power`, or on battery with `auto`
+    pub low_power: bool,
+    pub last_render: Option<chrono::DateTime<chrono::Local>>,
+    // NOTE: a render held back by the low power mode
+    pub render_pending: bool,
+    pub updates: Shared<Updates>,
+    pub auth: Shared<Auth>,
+    pub http: Shared<Http>,
+    pub environment: Shared<Environment>,
+    pub panes: Shared<Panes>,
+    pub cmd: Shared<CmdDuration>,
+    pub usage: Shared<Usage>,
+    pub git: Shared<Git>,
+    pub buttons: Shared<Buttons>,
+    pub floating: Shared<Floating>,
+    pub clock: Shared<Clock>,
+    pub tabs: Shared<Tabs>,
+    pub text: Shared<Text>,
+    pub script: Shared<Script>,
+    pub history: History,
+    pub recorder: Recorder,
+    pub polls: Vec<Poll>,
+    pub breakpoints: Breakpoints,
+    pub placeholders: Placeholders,
+    pub profiles: Profiles,
+    // NOTE: profile applied by the last reload
+    pub profile: Option<String>,
+    pub visibility: Visibility,
+    pub hidden: Hidden,
+    pub rules: Rules,
+    pub human: HumanFormat,
+    pub wizard: Option<Wizard>,
+    pub menu: Option<Menu<MenuAction>>,
+    pub confirm: Option<Confirm<MenuAction>>,
+    pub focus: Focus,
+    pub weekly_summary_at: Option<chrono::NaiveTime>,
+    pub toast: Option<String>,
+    // NOTE: why the configured theme file wasn't loaded, shown along with configuration errors
+    pub theme_error: Option<String>,
+    // NOTE: widths of the last render drawn in the toast row with `debug`, toasts go first
+    pub debug: Option<String>,
+    // NOTE: row of the pane the toast is drawn in, `None` when it replaces the bar
+    pub toast_row: Option<usize>,
+    // NOTE: set for toasts that dismiss themselves
+    pub toast_expires: Option<chrono::DateTime<chrono::Local>>,
+    // NOTE: clients connected to the current session as of the last session update
+    pub clients: Option<usize>,
+    // NOTE: output of the last render, reused across renders
+    pub frame: String,
+    pub zen: bool,
+    // NOTE: when zen was last toggled, while the new layout wipes in
+    pub zen_since: Option<chrono::DateTime<chrono::Local>>,
+    // NOTE: when the periodic timer is due, timers firing before it belong to someone else
+    pub next_tick: Option<chrono::DateTime<chrono::Local>>,
+    // NOTE: rendered by `preview` outside of zellij, host calls would trap
+    pub headless: bool,
+    // NOTE: nothing but stale defaults would be shown without the permissions
+    pub permission_denied: bool,
+    pub autohide: bool,
+    pub accessible: bool,
 
-    pub left_elements: Vec<Box<dyn Display>>,
-    pub right_elements: Vec<Box<dyn Display>>,
+    // NOTE: columns occupied by each element during the last render, used for mouse handling
+    pub regions: Vec<(&'static str, Range<usize>)>,
+    pub dragged: Option<&'static str>,
+    // NOTE: element highlighted by keyboard navigation, along with the tab or button within it
+    pub selected: Option<(&'static str, usize)>,
+    // NOTE: dragging an element onto another one reorders them instead of being a click
+    pub arranging: bool,
+    // NOTE: columns the bar is scrolled by while its content doesn't fit, and by how much it
+    // didn't during the last render
+    pub scroll: usize,
+    pub scroll_until: Option<chrono::DateTime<chrono::Local>>,
+    // NOTE: last input of the local client, tells its own tab switches from external ones
+    pub last_input: Option<chrono::DateTime<chrono::Local>>,
+    pub flash_until: Option<chrono::DateTime<chrono::Local>>,
+    pub overflow: usize,
+    // NOTE: cells the tabs were given during the last render, clicks are mapped onto the
+    // representation picked for them
+    pub tabs_cells: usize,
+
+    // NOTE: every widget by name, events are dispatched to all of them whether shown or not
+    pub widgets: Vec<(&'static str, Shared<dyn Widget>)>,
+
+    pub left_elements: Vec<(&'static str, Shared<dyn Widget>)>,
+    pub center_elements: Vec<(&'static str, Shared<dyn Widget>)>,
+    pub right_elements: Vec<(&'static str, Shared<dyn Widget>)>,
 }
 
-// NOTE: Plugin configuration keys
-const TZ_STRING: &str = "timezone";
-const SELECTABLE: &str = "selectable";
+// NOTE: Plugin configuration keys, the rest is read through the typed `config::Config`
+const LEFT: &str = "left";
+const RIGHT: &str = "right";
+const CENTER: &str = "center";
+const CONFIG_FILE: &str = "config_file";
+
+// NOTE: how long attach/detach toasts and the order of moved elements stay up
+const CLIENT_TOAST_SECONDS: i64 = 5;
+
+// NOTE: elements doing something when clicked as a whole, reachable with keyboard navigation
+const CLICKABLE: &[&str] = &[
+    "diagnostics",
+    "errors",
+    "custom",
+    "floating",
+    "updates",
+    "git",
+    "fill",
+];
+
+// NOTE: how long destructive menu actions wait for the confirming click
+const CONFIRM_SECONDS: f64 = 2.0;
+
+// NOTE: columns moved per wheel step over an overflowing bar, and how long it stays scrolled
+const SCROLL_STEP: usize = 8;
+const SCROLL_SECONDS: i64 = 5;
+
+// NOTE: polls run this many times less often in low power mode, renders at most this often
+const LOW_POWER_FACTOR: f64 = 4.0;
+const LOW_POWER_RENDER_SECONDS: f64 = 5.0;
+
+// NOTE: tab switches this long after the last input came from somewhere else - another client,
+// a script driving `zellij action`
+const EXTERNAL_SWITCH_SECONDS: i64 = 1;
+
+// NOTE: the layout toggled to with zen wipes in from the left over this many timer steps
+const ZEN_TRANSITION_SECONDS: f64 = 0.3;
+const ZEN_TRANSITION_STEPS: usize = 6;
+
+// NOTE: period of the timer kept running for what changes with time alone - stale tabs fading
+// and the focus time flushed
+const TICK_SECONDS: f64 = 30.0;
 
 register_plugin!(PluginState);
+register_worker!(Worker, statusline_worker, STATUSLINE_WORKER);
 
 impl ZellijPlugin for PluginState {
     fn load(&mut self, configuration: BTreeMap<String, String>) {
-        request_permission(&[PermissionType::ReadApplicationState]);
-        subscribe(&[
-            EventType::ModeUpdate,
-            EventType::SessionUpdate,
-            EventType::TabUpdate,
-        ]);
+        compat::detect();
+        request_permission(&compat::permissions());
 
-        self.config = configuration;
-        self.session = Shared::new("default".into());
+        self.hidden = Hidden::load();
+        self.focus = Focus::load();
+        self.usage = Shared::new(Usage::load());
+        self.panes = Shared::new(Panes::new(get_plugin_ids().plugin_id));
+        self.widgets = self.registry();
 
-        if let Some(value) = self.config.get(SELECTABLE) {
-            let selectable: bool = value.parse().unwrap_or(false);
-            set_selectable(selectable);
+        // INFO: events handled by the plugin itself and the ones widgets are interested in
+        let mut events = vec![
+            EventType::TabUpdate,
+            EventType::Key,
+            EventType::Mouse,
+            EventType::Timer,
+            EventType::RunCommandResult,
+            EventType::CustomMessage,
+            EventType::PermissionRequestResult,
+            EventType::InputReceived,
+        ];
+        for (_, widget) in &self.widgets {
+            for event in widget.borrow().interested_events() {
+                if !events.contains(&event) {
+                    events.push(event);
+                }
+            }
         }
+        subscribe(&events);
 
-        // NOTE: create segments
-        // INFO: MODE
-        // Not internally mutable without `update` call - we can render it to String
-        let mode = Mode(InputMode::Normal);
-        let segment = Segment::new(
-            Box::new(mode),
-            AnsiStyle::new()
-                .bg_color(mode.color())
-                .fg_color(*BLACK)
-                .bold(),
-        )
-        .min_width(10);
-        *self.mode.borrow_mut() = segment.to_string();
-        self.left_elements.push(Box::new(self.mode.clone()));
+        // NOTE: guide the user through the basic options when started without any configuration
+        if Wizard::should_run(&configuration) {
+            self.wizard = Some(Wizard::default());
+        }
 
-        // INFO: SESSION
-        // Not internally mutable without `update` call - we can render it to String
-        let segment = Segment::new(
-            Box::new("default"),
-            AnsiStyle::new().fg_color(*BLACK).bg_color(*GREEN),
-        )
-        .min_width(10);
-        self.left_elements.push(Box::new(segment.to_string()));
+        self.plugin_config = configuration;
+        self.reload();
 
-        // INFO: CLOCK
-        // This segment actually change its display, so we are not prerendering it
-        let segment = Segment::new(
-            Box::new(Clock::new(self.config.get(TZ_STRING))),
-            AnsiStyle::new().bg_color(*WHITE).fg_color(*BLACK),
-        )
-        .max_width(64);
-        self.right_elements.push(Box::new(segment));
+        // INFO: first poll once the permissions had a chance to be granted
+        set_timeout(1.0);
     }
 
     fn update(&mut self, event: Event) -> bool {
+        if self.recorder.is_recording() {
+            self.recorder.record(Entry::Event {
+                at: recorder::now(),
+                event: event.clone(),
+            });
+        }
         let mut should_render = false;
-        match event {
-            Event::ModeUpdate(event) => {
-                let mode = Mode(event.mode);
-                let segment = Segment::new(
-                    Box::new(mode),
-                    AnsiStyle::new()
-                        .bg_color(mode.color())
-                        .fg_color(*BLACK)
-                        .bold(),
-                )
-                .min_width(10);
+        let active_tab = self.tabs.borrow().active_position();
+        let locked = self.locked_palette();
+        for (_, widget) in &self.widgets {
+            should_render |= widget::dispatch(&mut *widget.borrow_mut(), &event);
+        }
+        // INFO: segments remembered with the other palette
+        if self.locked_palette() != locked {
+            self.memo.clear();
+            should_render = true;
+        }
+        should_render |= widget::dispatch(&mut self.errors, &event);
+        if self.power.borrow().on_battery() != self.on_battery {
+            self.reload();
+            should_render = true;
+        }
 
-                // INFO: render updated state to String
-                *self.mode.borrow_mut() = segment.to_string();
+        let interactive = matches!(event, Event::Key(_) | Event::Mouse(_));
+        match event {
+            Event::PermissionRequestResult(status) => {
+                self.permission_denied = status == PermissionStatus::Denied;
+                return true;
+            }
+            // INFO: clicking the notice asks again
+            Event::Mouse(Mouse::LeftClick(..)) if self.permission_denied => {
+                self.request_permissions();
+                return false;
+            }
+            Event::Key(_) | Event::Mouse(_) if self.wizard.is_some() => {
+                return self.update_wizard(event);
+            }
+            Event::Key(_) | Event::Mouse(_) if self.confirm.is_some() => {
+                return self.update_confirm(&event);
+            }
+            Event::Key(_) | Event::Mouse(_) if self.menu.is_some() => {
+                return self.update_menu(&event);
+            }
+            Event::Key(_) | Event::Mouse(_) if self.history.is_open() => {
+                return self.history.handle(&event);
+            }
+            Event::Key(key) if self.tabs.borrow().filter().is_some() => {
+                return self.update_filter(key);
+            }
+            Event::Key(_) | Event::Mouse(Mouse::LeftClick(..) | Mouse::RightClick(..))
+                if self.toast.is_some() && self.dismisses_toast(&event) =>
+            {
+                self.toast = None;
+                self.toast_expires = None;
+                return true;
+            }
+            // INFO: keyboard navigation of tabs, buttons and the other clickable elements while the
+            // plugin is focused, starting from the active tab
+            Event::Key(Key::Left | Key::Right) if !self.targets().is_empty() => {
+                let targets = self.targets();
+                let count = targets.len();
+                let active = self.tabs.borrow().active_position();
+                let current = self
+                    .selected
+                    .or(active.map(|x| ("tabs", x)))
+                    .and_then(|x| targets.iter().position(|y| *y == x))
+                    .unwrap_or(0);
+                let selected = match event {
+                    Event::Key(Key::Left) => (current + count - 1) % count,
+                    _ => (current + 1) % count,
+                };
+                self.select(Some(targets[selected]));
                 should_render = true;
             }
-            Event::SessionUpdate(sessions) => {
-                for session in sessions {
-                    if session.is_current_session {
-                        let segment = Segment::new(
-                            Box::new(session.name),
-                            AnsiStyle::new().bg_color(*GREEN).fg_color(*BLACK),
-                        )
-                        .min_width(10);
-                        //
-                        // INFO: render updated state to String
-                        *self.session.borrow_mut() = segment.to_string();
-
-                        should_render = true;
-                        break;
+            Event::Key(Key::Char('\n')) => {
+                if let Some((name, index)) = self.selected {
+                    self.select(None);
+                    match name {
+                        "tabs" => switch_tab_to(index as u32 + 1),
+                        _ => {
+                            let start = self
+                                .regions
+                                .iter()
+                                .find(|(x, _)| *x == name)
+                                .map(|(_, region)| region.start);
+                            let offset = match name {
+                                "buttons" => self.buttons.borrow().offset(index),
+                                _ => 0,
+                            };
+                            if let Some(start) = start {
+                                self.click(name, start + offset);
+                            }
+                        }
                     }
+                    should_render = true;
                 }
             }
+            Event::Key(Key::Esc) if self.selected.is_some() => {
+                self.select(None);
+                should_render = true;
+            }
+            Event::Key(Key::Char('/')) if self.tabs.borrow().count() > 0 => {
+                self.tabs.borrow_mut().set_filter(Some(String::new()));
+                should_render = true;
+            }
+            Event::Key(Key::Char('m')) | Event::Mouse(Mouse::RightClick(..)) => {
+                self.menu = Some(Menu::new(
+                    &i18n::tr("actions"),
+                    vec![
+                        (i18n::tr("new_tab"), MenuAction::NewTab),
+                        (i18n::tr("detach"), MenuAction::Detach),
+                        (i18n::tr("lock"), MenuAction::Lock),
+                        (i18n::tr("zen"), MenuAction::Zen),
+                        (i18n::tr("close_tab"), MenuAction::CloseTab),
+                        (
+                            i18n::tr("clear_notifications"),
+                            MenuAction::ClearNotifications,
+                        ),
+                        (i18n::tr("quit"), MenuAction::Quit),
+                    ],
+                ));
+                should_render = true;
+            }
+            Event::ModeUpdate(mode_info) if self.settings.use_zellij_theme => {
+                let palette = theme::from_zellij(&mode_info.style.colors);
+                if self.zellij_palette != Some(palette) {
+                    self.zellij_palette = Some(palette);
+                    self.palette = palette;
+                    self.tabs.borrow_mut().refresh(&self.palette);
+                    self.memo.clear();
+                    self.check_contrast();
+                    should_render = true;
+                }
+            }
+            Event::InputReceived => {
+                self.last_input = Some(time::now());
+            }
             Event::TabUpdate(tabs) => {
-                self.tabs = Tabs::new(tabs);
+                let active = self.tabs.borrow().active_position();
+                if active_tab.is_some() && active != active_tab {
+                    should_render |= self.flash_if_external();
+                }
+                if self.weekly_summary_at.is_some() {
+                    if let Some(tab) = tabs.iter().find(|x| x.active) {
+                        self.focus.switch(&tab.name);
+                    }
+                }
+            }
+            Event::SessionUpdate(..) => {
+                if let Some(sessions) = compat::sessions(&event) {
+                    should_render |= self.update_clients(sessions);
+                }
+            }
+            Event::Timer(_) => {
+                for poll in &mut self.polls {
+                    poll.tick();
+                }
+                // INFO: timers may fire slightly early
+                if self
+                    .next_tick
+                    .is_some_and(|x| x <= time::now() + chrono::Duration::milliseconds(100))
+                {
+                    self.schedule_tick();
+                }
+                if self.weekly_summary_at.is_some() {
+                    self.focus.flush(false);
+                    should_render |= self.weekly_summary();
+                }
+                if self.scroll_until.is_some_and(|x| time::now() > x) {
+                    self.scroll = 0;
+                    self.scroll_until = None;
+                    should_render = true;
+                }
+                if self.toast_expires.is_some_and(|x| time::now() > x) {
+                    self.toast = None;
+                    self.toast_expires = None;
+                    should_render = true;
+                }
+                should_render |= self.zen_since.is_some();
+                if self.flash_until.is_some_and(|x| time::now() > x) {
+                    self.flash_until = None;
+                    should_render |= self.tabs.borrow_mut().set_flashing(false);
+                }
+                should_render |= self.update_confirm(&event);
+                if self.profiles.active(time::now()) != self.profile.as_deref() {
+                    self.reload();
+                    should_render = true;
+                }
+            }
+            // NOTE: `prefer-dark` of gsettings, `Dark` of macOS defaults - which fails when light
+            Event::RunCommandResult(_, stdout, _, context)
+                if context.get(poll::POLL).map(String::as_str) == Some("appearance") =>
+            {
+                let dark = String::from_utf8_lossy(&stdout)
+                    .to_lowercase()
+                    .contains("dark");
+                if self.dark != Some(dark) {
+                    self.dark = Some(dark);
+                    (self.palette, self.theme_error) = self.palette(&self.settings);
+                    self.toast_theme_error();
+                    self.tabs.borrow_mut().refresh(&self.palette);
+                    self.memo.clear();
+                    self.check_contrast();
+                    should_render = true;
+                }
+            }
+            Event::RunCommandResult(_, stdout, _, context)
+                if context.get(poll::POLL).map(String::as_str) == Some(worker::GIT_WORKTREES) =>
+            {
+                worker::post(worker::GIT_WORKTREES, &String::from_utf8_lossy(&stdout));
+            }
+            Event::CustomMessage(name, payload) if name == worker::GIT_WORKTREES => {
+                let worktrees: Vec<(String, String)> =
+                    serde_json::from_str(&payload).unwrap_or_default();
+                let items = worktrees
+                    .into_iter()
+                    .map(|(path, branch)| {
+                        (branch.clone(), MenuAction::OpenWorktree { path, branch })
+                    })
+                    .collect();
+                self.menu = Some(Menu::new(&i18n::tr("worktrees"), items));
                 should_render = true;
             }
+            // INFO: the wheel brings elements that didn't fit into view for a while
+            Event::Mouse(Mouse::ScrollDown(_)) if self.overflow > 0 => {
+                should_render = self.scroll_to(self.scroll + SCROLL_STEP);
+            }
+            Event::Mouse(Mouse::ScrollUp(_)) if self.scroll > 0 => {
+                should_render = self.scroll_to(self.scroll.saturating_sub(SCROLL_STEP));
+            }
+            // INFO: and cycles the tabs once everything is in view, wherever it's pointed at
+            Event::Mouse(Mouse::ScrollDown(_)) if self.overflow == 0 => go_to_next_tab(),
+            Event::Mouse(Mouse::ScrollUp(_)) if self.overflow == 0 => go_to_previous_tab(),
+            // INFO: drag an element onto another one on the same side to swap their order while
+            // arranging
+            Event::Mouse(Mouse::LeftClick(_, col)) => {
+                self.dragged = self.element_at(col);
+            }
+            Event::Mouse(Mouse::Release(_, col)) => {
+                match (self.dragged.take(), self.element_at(col)) {
+                    (Some(from), Some(to)) if from == to => should_render = self.click(from, col),
+                    (Some(from), Some(to)) if self.arranging => {
+                        should_render = self.move_element(from, to)
+                    }
+                    _ => {}
+                }
+            }
             _ => {}
         }
 
-        should_render
+        self.coalesce(should_render, interactive)
     }
 
-    fn render(&mut self, _: usize, cols: usize) {
-        let mut chars = 0;
+    fn pipe(&mut self, pipe_message: PipeMessage) -> bool {
+        if !compat::pipes() {
+            return false;
+        }
+        if self.recorder.is_recording() {
+            self.recorder.record(Entry::Pipe {
+                at: recorder::now(),
+                name: pipe_message.name.clone(),
+                payload: pipe_message.payload.clone(),
+                args: pipe_message.args.clone(),
+            });
+        }
+        let request = match pipe::dispatch(&pipe_message, self.settings.pipe_max_width) {
+            Ok(Some(request)) => request,
+            Ok(None) => return false,
+            Err(err) => {
+                pipe::reply(&pipe_message, &format!("error: {err}"));
+                return false;
+            }
+        };
 
-        // NOTE: render left segments
-        for s in &self.left_elements {
-            chars += s.display_len();
-            print!("{s}");
+        let should_render = match request {
+            Request::DumpConfig => {
+                let reply = match config::dump(&self.config) {
+                    Ok(path) => format!("configuration written to {path}"),
+                    Err(err) => format!("error: failed to write configuration: {err}"),
+                };
+                pipe::reply(&pipe_message, &reply);
+                false
+            }
+            // INFO: meant to be bound to a key with `MessagePlugin`
+            Request::Zen => {
+                self.toggle_zen();
+                true
+            }
+            Request::Arrange => {
+                self.arranging = !self.arranging;
+                self.dragged = None;
+                false
+            }
+            Request::Toggle(name) => {
+                self.hidden.toggle(&name);
+                true
+            }
+            Request::History => {
+                self.history.open();
+                true
+            }
+            // INFO: meant to be bound to a key with `MessagePlugin`, keys only reach focused panes
+            Request::Filter => {
+                if self.tabs.borrow().count() == 0 {
+                    return false;
+                }
+                if !self.headless {
+                    set_selectable(true);
+                    focus_plugin_pane(get_plugin_ids().plugin_id, false);
+                }
+                self.tabs.borrow_mut().set_filter(Some(String::new()));
+                true
+            }
+            Request::Permissions => {
+                self.request_permissions();
+                false
+            }
+            Request::ModeUsage => {
+                let usage = self.usage.borrow();
+                let reply = if usage.enabled {
+                    let totals = |days| {
+                        let totals: Vec<String> = usage
+                            .totals(days)
+                            .iter()
+                            .map(|(mode, seconds)| {
+                                format!("{mode} {}", self.human.duration(*seconds as f64))
+                            })
+                            .collect();
+                        totals.join(" · ")
+                    };
+                    format!(
+                        "{}\n{}",
+                        i18n::tr("usage_today").replace("{totals}", &totals(1)),
+                        i18n::tr("usage_week").replace("{totals}", &totals(7))
+                    )
+                } else {
+                    i18n::tr("usage_disabled")
+                };
+                pipe::reply(&pipe_message, &reply);
+                false
+            }
+            Request::Record => {
+                let reply = match self.recorder.toggle(&self.config) {
+                    Ok(true) => format!("recording events to {}", recorder::RECORDING_PATH),
+                    Ok(false) => "recording stopped".to_string(),
+                    Err(err) => format!("error: failed to start recording: {err}"),
+                };
+                pipe::reply(&pipe_message, &reply);
+                false
+            }
+            // INFO: picks up changes of `config_file` and the zellij theme file
+            Request::Reload => {
+                self.reload();
+                pipe::reply(&pipe_message, "configuration reloaded");
+                true
+            }
+            Request::Visibility { name, hidden } => {
+                if !self.custom.borrow_mut().set_hidden(&name, hidden) {
+                    self.hidden.set(&name, hidden);
+                }
+                true
+            }
+            Request::Protect(name) => {
+                self.tabs.borrow_mut().toggle_protected(name);
+                true
+            }
+            Request::Segment(envelope) => {
+                let formatted = envelope.value.map(|value| {
+                    let kind = envelope.format.as_deref().unwrap_or("number");
+                    self.human
+                        .format(kind, value)
+                        .unwrap_or_else(|| value.to_string())
+                });
+                let text = match (envelope.text, formatted) {
+                    (Some(text), Some(formatted)) => text.replace("{value}", &formatted),
+                    (Some(text), None) => text,
+                    (None, Some(formatted)) => formatted,
+                    (None, None) => return self.custom.borrow_mut().remove(&envelope.name),
+                };
+                let fg = envelope
+                    .fg
+                    .as_deref()
+                    .and_then(parse_color)
+                    .or(self.palette.black);
+                let bg = envelope
+                    .bg
+                    .as_deref()
+                    .and_then(parse_color)
+                    .or(self.palette.cyan);
+                if let Some(ttl) = envelope.ttl.filter(|_| !self.headless) {
+                    set_timeout(ttl);
+                }
+
+                // INFO: `rule_<name>` thresholds override the piped colors
+                let mut style = AnsiStyle::new().fg_color(fg).bg_color(bg);
+                if let Some(value) = envelope.value {
+                    style = self.rules.style(&envelope.name, value, style);
+                }
+                self.history.push(&envelope.name, &text);
+                self.custom.borrow_mut().set(
+                    envelope.name,
+                    text,
+                    style,
+                    envelope.ttl,
+                    envelope.value,
+                );
+                true
+            }
+            Request::Progress { tab, percent } => {
+                self.tabs.borrow_mut().set_progress(tab, percent);
+                true
+            }
+            Request::Cmd {
+                pane,
+                finished,
+                at,
+                exit,
+            } => {
+                let mut cmd = self.cmd.borrow_mut();
+                if finished {
+                    cmd.end(pane, at, exit)
+                } else {
+                    cmd.start(pane, at);
+                    false
+                }
+            }
+            Request::Context { text, fg, bg } => {
+                let context = text.map(|text| {
+                    let fg = fg.as_deref().and_then(parse_color).or(self.palette.black);
+                    let bg = bg.as_deref().and_then(parse_color).or(self.palette.magenta);
+                    (text, AnsiStyle::new().fg_color(fg).bg_color(bg))
+                });
+                *self.context.borrow_mut() = Context(context);
+                true
+            }
+        };
+        self.coalesce(should_render, false)
+    }
+
+    // NOTE: margins are left unpainted, mouse regions are shifted past the left one
+    fn render(&mut self, rows: usize, cols: usize) {
+        self.recorder.record(Entry::Render {
+            at: recorder::now(),
+            rows,
+            cols,
+        });
+        self.render_frame(rows, cols);
+        print!("{}", self.frame);
+        self.last_render = Some(time::now());
+        self.render_pending = false;
+    }
+}
+
+impl PluginState {
+    // NOTE: keys set in the plugin block win over the ones of `config_file`, which is read again
+    // on every reload
+    // NOTE: every row of the pane is written into the reused `frame` buffer and printed at once
+    fn render_frame(&mut self, rows: usize, cols: usize) {
+        let (left, right) = (self.settings.margin_left, self.settings.margin_right);
+        // INFO: decided before the bar renders, so the toast isn't drawn inline as well
+        self.toast_row = (rows >= 2 && self.settings.toast_area == ToastArea::Auto).then_some(0);
+        let width = cols.saturating_sub(left + right);
+        // NOTE: whatever took over the bar, the pane never gets more than its columns
+        let line = budget::limit(&self.render_to_string(width), width);
+        let line = match self.zen_progress() {
+            Some(progress) => {
+                let shown = (width as f64 * progress) as usize;
+                format!(
+                    "{}{}",
+                    budget::limit(&line, shown),
+                    fill(width - shown, " ", &self.palette)
+                )
+            }
+            None => line,
+        };
+        for (_, region) in &mut self.regions {
+            *region = region.start + left..region.end + left;
+        }
+        if let Some(menu) = &mut self.menu {
+            menu.shift(left);
+        }
+
+        let row = match self.settings.valign {
+            VAlign::Top => 0,
+            VAlign::Middle => rows.saturating_sub(1) / 2,
+            VAlign::Bottom => rows.saturating_sub(1),
+        };
+        let toast = self
+            .toast
+            .as_ref()
+            .filter(|_| self.toast_row.is_some())
+            .map(|x| budget::limit(&self.render_toast(x, width, &self.palette), width));
+        let toasted = toast.is_some();
+        let toast = toast.or_else(|| {
+            let debug = self.debug.as_ref().filter(|_| rows >= 2)?;
+            Some(budget::limit(debug, width))
+        });
+
+        self.frame.clear();
+        // INFO: the toast row goes below the bar, above it when the bar is at the bottom
+        let (first, lines) = match &toast {
+            Some(toast) if row + 1 < rows => {
+                self.toast_row = Some(row + 1);
+                (row, [Some(line.as_str()), Some(toast.as_str())])
+            }
+            Some(toast) => {
+                self.toast_row = Some(row - 1);
+                (row - 1, [Some(toast.as_str()), Some(line.as_str())])
+            }
+            None => (row, [Some(line.as_str()), None]),
+        };
+        // INFO: the debug row isn't a toast, clicks on it don't dismiss an inline one
+        if !toasted {
+            self.toast_row = None;
+        }
+        self.frame.push_str(&"\n".repeat(first));
+        for (index, content) in lines.into_iter().flatten().enumerate() {
+            if index > 0 {
+                self.frame.push('\n');
+            }
+            let _ = write!(self.frame, "{:left$}{content}\x1b[0m", "");
+        }
+    }
+
+    // NOTE: renders are held back to one every `LOW_POWER_RENDER_SECONDS` in low power mode, a
+    // timer catches up with the ones held back. Keys and clicks are answered right away.
+    fn coalesce(&mut self, should_render: bool, interactive: bool) -> bool {
+        if !self.low_power || interactive || !(should_render || self.render_pending) {
+            return should_render;
+        }
+        let elapsed = self.last_render.map_or(f64::MAX, |x| {
+            (time::now() - x).num_milliseconds() as f64 / 1000.0
+        });
+        if elapsed >= LOW_POWER_RENDER_SECONDS {
+            return true;
+        }
+        if !self.render_pending && !self.headless {
+            self.render_pending = true;
+            set_timeout(LOW_POWER_RENDER_SECONDS - elapsed);
+        }
+        false
+    }
+
+    // NOTE: last week's focus time, shown once on the first timer after `weekly_summary_at` on Monday
+    fn weekly_summary(&mut self) -> bool {
+        let Some(tabs) = self
+            .weekly_summary_at
+            .and_then(|at| self.focus.weekly_summary(at))
+            .filter(|x| !x.is_empty())
+        else {
+            return false;
+        };
+        let tabs: Vec<String> = tabs
+            .iter()
+            .take(5)
+            .map(|(name, seconds)| format!("{name} {}", self.human.duration(*seconds as f64)))
+            .collect();
+        self.toast = Some(format!(
+            "{} {}",
+            i18n::tr("weekly_summary"),
+            tabs.join(" · ")
+        ));
+        self.toast_expires = None;
+        true
+    }
+
+    // INFO: arms the periodic timer again, the one pending gets ignored once `next_tick` moved on
+    fn schedule_tick(&mut self) {
+        let needed = self.tabs.borrow().stale_after > 0.0 || self.weekly_summary_at.is_some();
+        if !needed || self.headless {
+            self.next_tick = None;
+            return;
+        }
+        self.next_tick =
+            Some(time::now() + chrono::Duration::milliseconds((TICK_SECONDS * 1000.0) as i64));
+        set_timeout(TICK_SECONDS);
+    }
+
+    fn request_permissions(&self) {
+        if !self.headless {
+            request_permission(&compat::permissions());
+        }
+    }
+
+    fn reload(&mut self) {
+        let mut configuration = self.plugin_config.clone();
+        let mut file_error = None;
+        if let Some(path) = configuration.get(CONFIG_FILE) {
+            match ConfigFile::load(path) {
+                Ok(file) => {
+                    let mut merged = file.flatten();
+                    merged.append(&mut configuration);
+                    configuration = merged;
+                }
+                Err(err) => file_error = Some(err),
+            }
+        }
+
+        // INFO: the profile of the time of day, the timer reloads again once the next one is due
+        let now = time::now();
+        self.profiles = Profiles::new(&configuration);
+        self.profile = self.profiles.active(now).map(String::from);
+        if let Some(profile) = &self.profile {
+            profiles::apply(profile, &mut configuration);
+        }
+        // INFO: the battery profile takes over from the scheduled one until the AC is back
+        self.on_battery = self.power.borrow().on_battery();
+        if let Some(profile) = configuration
+            .get("battery_profile")
+            .cloned()
+            .filter(|_| self.on_battery)
+        {
+            profiles::apply(&profile, &mut configuration);
+        }
+        if let Some(seconds) = self.profiles.next_switch(now).filter(|_| !self.headless) {
+            set_timeout(seconds + 0.1);
+        }
+
+        self.config = config::effective(&configuration);
+        self.build();
+        self.schedule_tick();
+
+        self.toast = file_error.map(|err| i18n::tr("config_file_error").replace("{error}", &err));
+        self.toast_expires = None;
+        if self.toast.is_none() {
+            self.toast_theme_error();
+        }
+    }
+
+    // INFO: a click only dismisses a toast drawn in its own row when it lands on that row
+    fn dismisses_toast(&self, event: &Event) -> bool {
+        match (event, self.toast_row) {
+            (Event::Mouse(Mouse::LeftClick(line, _) | Mouse::RightClick(line, _)), Some(row)) => {
+                *line == row as isize
+            }
+            _ => true,
+        }
+    }
+
+    fn render_toast(&self, toast: &str, cols: usize, palette: &Palette) -> String {
+        let segment = Segment::new(
+            Box::new(toast.to_string()),
+            AnsiStyle::new()
+                .fg_color(palette.black)
+                .bg_color(palette.blue),
+            palette,
+        )
+        .max_width(cols.saturating_sub(4));
+        let gap = cols.saturating_sub(segment.display_len());
+        let before = match self.settings.toast_align {
+            HAlign::Left => 0,
+            HAlign::Center => gap / 2,
+            HAlign::Right => gap,
+        };
+        format!(
+            "{}{segment}{}",
+            fill(before, " ", palette),
+            fill(gap - before, " ", palette)
+        )
+    }
+
+    // NOTE: whole bar as a single line, shared by the plugin and the headless `preview`
+    fn render_to_string(&mut self, cols: usize) -> String {
+        let palette = match self.locked_palette() {
+            true => self.palette.locked(),
+            false => self.palette,
+        };
+        self.render_line(cols, &palette)
+    }
+
+    // NOTE: the elements go gray while locked with `locked_palette`, the tabs keep their colors
+    fn locked_palette(&self) -> bool {
+        self.settings.locked_palette && *self.mode.borrow() == InputMode::Locked
+    }
+
+    fn render_line(&mut self, cols: usize, palette: &Palette) -> String {
+        if self.permission_denied {
+            let segment = Segment::new(
+                Box::new(i18n::tr("grant_permission")),
+                AnsiStyle::new()
+                    .fg_color(palette.black)
+                    .bg_color(palette.yellow)
+                    .bold(),
+                palette,
+            )
+            .max_width(cols.saturating_sub(4));
+            let width = segment.display_len();
+            return format!(
+                "{segment}{}",
+                fill(cols.saturating_sub(width), " ", palette)
+            );
+        }
+
+        if let Some(wizard) = &self.wizard {
+            let segment = Segment::new(
+                Box::new(wizard.to_string()),
+                AnsiStyle::new()
+                    .bg_color(palette.cyan)
+                    .fg_color(palette.black),
+                palette,
+            )
+            .max_width(cols.saturating_sub(4));
+            return segment.to_string();
+        }
+
+        if let Some(confirm) = &self.confirm {
+            let line = confirm.render(palette);
+            let width = line.display_len();
+            return format!("{line}{}", fill(cols.saturating_sub(width), " ", palette));
+        }
+
+        if let Some(menu) = &mut self.menu {
+            let line = menu.render(palette);
+            let width = line.display_len();
+            return format!("{line}{}", fill(cols.saturating_sub(width), " ", palette));
+        }
+
+        if self.history.is_open() {
+            let line = self.history.render(palette);
+            let width = line.display_len();
+            return format!("{line}{}", fill(cols.saturating_sub(width), " ", palette));
+        }
+
+        if let Some(toast) = self.toast.as_ref().filter(|_| self.toast_row.is_none()) {
+            return self.render_toast(toast, cols, palette);
+        }
+
+        if self.accessible {
+            let session = self.session.borrow();
+            let tabs = self.tabs.borrow();
+            let custom = self.custom.borrow();
+            let plain = Plain {
+                mode: self.mode.borrow().to_string(),
+                session: &session.0,
+                tabs: tabs.infos(),
+                custom: custom.texts(),
+                clock: self.clock.borrow().to_string(),
+            };
+            return plain.render(cols);
+        }
+
+        // NOTE: critical battery takes over the bar until charging or back above the threshold
+        if self.battery.borrow().is_critical() {
+            let segment = Segment::new(
+                Box::new(self.battery.borrow().warning()),
+                AnsiStyle::new()
+                    .fg_color(palette.white)
+                    .bg_color(palette.red)
+                    .bold()
+                    .blink(),
+                palette,
+            )
+            .max_width(cols.saturating_sub(4));
+            let width = segment.display_len();
+            return format!(
+                "{segment}{}",
+                fill(cols.saturating_sub(width), " ", palette)
+            );
+        }
+
+        self.regions.clear();
+
+        if self.zen {
+            return self.render_zen(cols, palette);
+        }
+
+        // NOTE: nothing interesting to show - single tab, normal mode, no piped segments and
+        // nothing failing or announced
+        if self.autohide
+            && *self.mode.borrow() == InputMode::Normal
+            && self.tabs.borrow().count() <= 1
+            && self.custom.borrow().is_empty()
+            && self.context.borrow().is_empty()
+            && self.errors.is_empty()
+            && self.diagnostics.is_empty()
+            && self.toast.is_none()
+        {
+            return fill(cols, "─", palette);
+        }
+
+        // NOTE: drop segments hidden by `hide_below_*` breakpoints for the current width, toggled
+        // off or failing their `<name>_visible` condition
+        let mode = format!("{:?}", self.mode.borrow().0).to_lowercase();
+        let facts = Facts {
+            mode: &mode,
+            tabs: self.tabs.borrow().count(),
+            cols,
+            now: time::now(),
+        };
+        let visible = |(name, _): &&(&str, Shared<dyn Widget>)| {
+            !self.breakpoints.is_hidden(name, cols)
+                && !self.hidden.contains(name)
+                && self.visibility.is_visible(name, &facts)
+        };
+
+        // NOTE: state the `text` template and the `script` element can refer to
+        {
+            let tabs = self.tabs.borrow();
+            let active = tabs.infos().iter().find(|x| x.active);
+            let vars = BTreeMap::from([
+                ("session", self.session.borrow().0.clone()),
+                ("mode", self.mode.borrow().to_string()),
+                ("tab", active.map(|x| x.name.clone()).unwrap_or_default()),
+                ("tab_count", tabs.count().to_string()),
+                ("clock", self.clock.borrow().to_string()),
+                ("cols", cols.to_string()),
+            ]);
+            self.script.borrow_mut().vars = vars.clone();
+            self.text.borrow_mut().vars = vars;
+        }
+
+        // NOTE: render every element once, tabs are rendered last with the space left by the others
+        let render =
+            |elements: &[(&'static str, Shared<dyn Widget>)]| -> Vec<(&'static str, Segment)> {
+                elements
+                    .iter()
+                    .filter(visible)
+                    .map(|(name, s)| match *name {
+                        "tabs" => (*name, Segment::empty()),
+                        _ if !s.borrow().is_loaded() => (
+                            *name,
+                            self.theme
+                                .apply(name, self.placeholders.render(name, palette)),
+                        ),
+                        _ => (
+                            *name,
+                            self.theme
+                                .apply(name, self.memo.render(name, &*s.borrow(), palette)),
+                        ),
+                    })
+                    .collect()
+            };
+        let mut segments = [
+            render(&self.left_elements),
+            render(&self.center_elements),
+            render(&self.right_elements),
+        ];
+
+        let errors = self.errors.render(palette);
+        if errors.display_len() > 0 {
+            segments[0].insert(0, ("errors", errors));
+        }
+        let diagnostics = self.diagnostics.render(palette);
+        if diagnostics.display_len() > 0 {
+            segments[0].insert(0, ("diagnostics", diagnostics));
+        }
+
+        // INFO: tabs and buttons highlight the selected one themselves
+        if let Some((name, _)) = self.selected.filter(|(x, _)| CLICKABLE.contains(x)) {
+            for (_, segment) in segments.iter_mut().flatten().filter(|(x, _)| *x == name) {
+                *segment = segment.clone().highlighted();
+            }
+        }
+
+        // NOTE: typing into a pane scrolled back goes nowhere - make reviewing history obvious.
+        // Zellij doesn't report per pane scroll state, the input mode is all there is.
+        let reviewing = matches!(
+            self.mode.borrow().0,
+            InputMode::Scroll | InputMode::Search | InputMode::EnterSearch
+        );
+        if reviewing {
+            let notice = Segment::new(
+                Box::new(i18n::tr("viewing_history")),
+                AnsiStyle::new()
+                    .fg_color(palette.black)
+                    .bg_color(palette.magenta)
+                    .bold(),
+                palette,
+            )
+            .priority(u8::MAX);
+            segments[2].push(("reviewing", notice));
+        }
+
+        // NOTE: the right group points the other way with `separator_right`
+        for (_, s) in &mut segments[2] {
+            *s = std::mem::replace(s, Segment::empty()).mirrored();
+        }
+
+        // NOTE: the other elements give way before the tabs have to fold
+        let tabs = segments.iter().flatten().any(|(name, _)| *name == "tabs");
+        let reserved = if tabs {
+            self.tabs.borrow().min_width()
+        } else {
+            0
+        };
+        // NOTE: nothing is dropped while scrolled, the line is as wide as its content
+        let natural: usize = segments
+            .iter()
+            .flatten()
+            .map(|(_, s)| s.render(usize::MAX).1)
+            .sum::<usize>()
+            + if tabs {
+                self.tabs.borrow().max_width()
+            } else {
+                0
+            };
+        self.overflow = natural.saturating_sub(cols);
+        self.scroll = self.scroll.min(self.overflow);
+        let scrolled = self.scroll > 0;
+        if !scrolled {
+            statusline::fit(&mut segments, cols.saturating_sub(reserved));
+        }
+        // INFO: the tabs placeholder is empty until substituted, the elements around it are not
+        // neighbours
+        for group in &mut segments {
+            for run in group.split_mut(|(name, _)| *name == "tabs") {
+                segment::join(run.iter_mut().map(|(_, s)| s));
+            }
+        }
+        let mut groups = segments.map(|x| {
+            x.into_iter()
+                .map(|(name, s)| (name, s.render(usize::MAX)))
+                .collect::<Vec<_>>()
+        });
+
+        // NOTE: tabs pick their representation in the cells left by the others
+        let used: usize = groups.iter().flatten().map(|(_, (_, width))| width).sum();
+        let cells = if scrolled {
+            usize::MAX
+        } else {
+            cols.saturating_sub(used)
+        };
+        for (name, s) in groups.iter_mut().flatten() {
+            if *name == "tabs" {
+                *s = RenderWidth::render(&*self.tabs.borrow(), cells);
+            }
+        }
+        self.tabs_cells = cells;
+        let widths: Vec<(&'static str, usize)> = groups
+            .iter()
+            .flatten()
+            .map(|(name, (_, width))| (*name, *width))
+            .collect();
+        let groups = groups.map(|x| {
+            x.into_iter()
+                .map(|(name, (s, _))| (name, s))
+                .collect::<Vec<_>>()
+        });
+
+        let fg = if reviewing {
+            palette.magenta
+        } else {
+            palette.gray
+        };
+        let [left, center, right] = groups;
+        let (line, regions) = Statusline::builder()
+            .left(left)
+            .center(center)
+            .right(right)
+            .fill(AnsiStyle::new().fg_color(fg).bg_color(palette.bg))
+            .offset(self.scroll)
+            .build()
+            .render(cols);
+        self.debug = self.settings.debug.then(|| {
+            let variant = tabs.then(|| self.tabs.borrow().variant(cells));
+            debug::overlay(&regions, &widths, variant, cols, palette)
+        });
+        self.regions = regions;
+        line
+    }
+
+    // INFO: snaps back to the start `SCROLL_SECONDS` after the last wheel step
+    fn scroll_to(&mut self, scroll: usize) -> bool {
+        let scroll = scroll.min(self.overflow);
+        if scroll == self.scroll {
+            return false;
+        }
+        self.scroll = scroll;
+        self.scroll_until =
+            (scroll > 0).then(|| time::now() + chrono::Duration::seconds(SCROLL_SECONDS));
+        if scroll > 0 && !self.headless {
+            set_timeout(SCROLL_SECONDS as f64 + 0.1);
+        }
+        true
+    }
+
+    // INFO: flashes the newly active tab for `tab_flash_seconds` unless the switch followed the
+    // input of this client
+    fn flash_if_external(&mut self) -> bool {
+        let seconds = self.settings.tab_flash_seconds;
+        let now = time::now();
+        let recent = self
+            .last_input
+            .is_some_and(|x| now - x < chrono::Duration::seconds(EXTERNAL_SWITCH_SECONDS));
+        if seconds <= 0.0 || recent {
+            return false;
+        }
+        self.flash_until = Some(now + chrono::Duration::milliseconds((seconds * 1000.0) as i64));
+        if !self.headless {
+            set_timeout(seconds + 0.1);
+        }
+        self.tabs.borrow_mut().set_flashing(true)
+    }
+
+    // INFO: toasts a client attaching to or detaching from the current session, the first update
+    // only records the count
+    fn update_clients(&mut self, sessions: &[SessionInfo]) -> bool {
+        let Some(session) = sessions.iter().find(|x| x.is_current_session) else {
+            return false;
+        };
+        let count = session.connected_clients;
+        let previous = self.clients.replace(count);
+        let key = match previous {
+            Some(previous) if count > previous => "client_attached",
+            Some(previous) if count < previous => "client_detached",
+            _ => return false,
+        };
+        if !self.settings.client_toasts {
+            return false;
+        }
+        self.toast = Some(i18n::tr(key).replace("{count}", &count.to_string()));
+        self.toast_expires = Some(time::now() + chrono::Duration::seconds(CLIENT_TOAST_SECONDS));
+        if !self.headless {
+            // INFO: wake up slightly after the deadline so the toast is surely expired by then
+            set_timeout(CLIENT_TOAST_SECONDS as f64 + 0.1);
+        }
+        true
+    }
+
+    // INFO: colors of the running zellij theme, derived from the theme file matching the desktop
+    // appearance, a zellij theme file or the built-in palette otherwise. Theme files failing to
+    // load are passed over, the first failure is returned along.
+    fn palette(&self, settings: &Config) -> (palette::Palette, Option<String>) {
+        let appearance = match self.dark {
+            Some(true) => settings.dark_theme_file.as_deref(),
+            Some(false) => settings.light_theme_file.as_deref(),
+            None => None,
+        };
+        if let Some(palette) = self.zellij_palette.filter(|_| settings.use_zellij_theme) {
+            return (palette, None);
+        }
+        let mut error = None;
+        for path in [appearance, settings.zellij_theme_file.as_deref()]
+            .into_iter()
+            .flatten()
+        {
+            match theme::load(path) {
+                Ok(palette) => return (palette, error),
+                Err(err) => {
+                    error.get_or_insert(err);
+                }
+            }
+        }
+        (palette::Palette::default(), error)
+    }
+
+    // INFO: theme files failing to load are reported like the configuration file
+    fn toast_theme_error(&mut self) {
+        if let Some(err) = &self.theme_error {
+            self.toast = Some(i18n::tr("theme_file_error").replace("{error}", err));
+            self.toast_expires = None;
+        }
+    }
+
+    // NOTE: (re)create segments from the current configuration
+    fn build(&mut self) {
+        let (settings, diagnostics) = config::parse(&self.config);
+        self.diagnostics.set(diagnostics);
+        self.errors = Errors::new(settings.errors_expanded);
+
+        self.breakpoints = Breakpoints::new(&self.config);
+        self.visibility = Visibility::new(&self.config);
+        self.placeholders = Placeholders::new(&self.config);
+        self.rules = Rules::new(&self.config);
+        self.human = HumanFormat::new(&settings);
+
+        // INFO: the wizard needs focus to receive keys
+        if !self.headless {
+            set_selectable(self.wizard.is_some() || settings.selectable);
+        }
+
+        (self.palette, self.theme_error) = self.palette(&settings);
+        self.theme = Theme::new(&self.config);
+
+        i18n::set_language(&settings.lang, &self.config);
+
+        self.accessible = settings.accessible;
+        self.mode
+            .borrow_mut()
+            .set_breadcrumb(settings.mode_breadcrumb);
+        self.autohide = settings.autohide;
+        self.weekly_summary_at = settings.weekly_summary_at;
+
+        // INFO: RFC 3339 instant pinned for every time-dependent element
+        time::set_fixed(settings.fixed_time);
+
+        self.low_power = match settings.low_power {
+            LowPower::Off => false,
+            LowPower::On => true,
+            LowPower::Auto => self.on_battery,
+        };
+        segment::set_animations(!self.low_power);
+        segment::set_extended_sgr(settings.extended_sgr);
+        segment::set_nerd_fonts(settings.nerd_fonts);
+        segment::set_separator(&settings.separator);
+        segment::set_separator_right(settings.separator_right.as_deref());
+        segment::set_ellipsis(&settings.ellipsis);
+        segment::set_padding(settings.padding_left, settings.padding_right);
+        segment::set_align(settings.align);
+        if settings.nerd_fonts {
+            caps::set_caps(settings.cap_left.as_deref(), settings.cap_right.as_deref());
+        } else {
+            caps::set_caps(None, None);
+        }
+        segment::set_tab_index(&settings.tab_index);
+
+        // INFO: WORKSPACE
+        *self.workspace.borrow_mut() = Workspace::new(
+            settings.workspace.as_ref(),
+            settings.workspace_colors.as_ref(),
+        );
+
+        // INFO: CLOCK
+        // INFO: the default format starts with a clock glyph
+        let clock_format = if settings.nerd_fonts {
+            settings.clock_format.clone()
+        } else {
+            accessible::strip_glyphs(&settings.clock_format)
+                .trim_start()
+                .to_string()
+        };
+        // INFO: minutes are enough in low power mode
+        let clock_format = if self.low_power {
+            clock::without_seconds(&clock_format)
+        } else {
+            clock_format
+        };
+        *self.clock.borrow_mut() =
+            Clock::new(settings.timezone, &clock_format, settings.numeral_locale);
+
+        // INFO: TABS
+        {
+            let mut tabs = self.tabs.borrow_mut();
+            tabs.placeholder = i18n::tr("starting");
+            tabs.suggest = settings.tab_suggestions;
+            tabs.stale_after = settings.tab_stale_minutes * 60.0;
+            tabs.refresh(&self.palette);
+        }
+
+        // INFO: CUSTOM
+        // Segments created through the `statusline` pipe
+        self.custom.borrow_mut().sparkline_width = settings.sparkline_width;
+        self.history.capacity = settings.history_size;
+
+        // INFO: BATTERY
+        self.battery.borrow_mut().critical = settings.battery_critical;
+
+        // INFO: HTTP
+        *self.http.borrow_mut() =
+            Http::new(settings.http_path.as_ref(), settings.http_colors.as_ref());
+
+        // INFO: ENVIRONMENT
+        *self.environment.borrow_mut() =
+            Environment::new(settings.env_vars.as_ref(), settings.env_colors.as_ref());
+
+        // INFO: TEXT
+        *self.text.borrow_mut() = Text::new(settings.text.clone().unwrap_or_default());
+
+        // INFO: SCRIPT
+        *self.script.borrow_mut() = Script::new(settings.script.as_deref());
+
+        // INFO: BUTTONS
+        *self.buttons.borrow_mut() = Buttons::new(&self.config);
+
+        // INFO: CMD DURATION
+        {
+            let mut cmd = self.cmd.borrow_mut();
+            cmd.human = self.human;
+            cmd.min_seconds = settings.cmd_duration_min;
+        }
+
+        // INFO: MODE USAGE
+        {
+            let mut usage = self.usage.borrow_mut();
+            usage.enabled = settings.mode_usage;
+            usage.human = self.human;
         }
 
-        // NOTE: eat right segments chars before rendering to let TABS know how much space they have left
-        for s in &self.right_elements {
+        // NOTE: `left | center | right` groups of element names, a single `|` means no center group
+        let layout = settings
+            .layout
+            .clone()
+            .unwrap_or_else(|| self.default_layout(&settings));
+        let mut groups: Vec<Vec<&str>> = layout
+            .split('|')
+            .map(|x| x.split_whitespace().collect())
+            .collect();
+        if groups.len() == 2 {
+            groups.insert(1, vec![]);
+        }
+        groups.resize_with(groups.len().max(3), Vec::new);
+        // NOTE: `left`, `center` and `right` replace their group of the layout -
+        // `left = "mode,session,git"`. Element names are the ids visibility rules, themes and
+        // breakpoints refer to as well.
+        let sides = [
+            (LEFT, settings.left.as_deref()),
+            (CENTER, settings.center.as_deref()),
+            (RIGHT, settings.right.as_deref()),
+        ];
+        for (group, (key, names)) in groups.iter_mut().zip(sides) {
+            if let Some(names) = names {
+                *group = names
+                    .split(|x: char| x == ',' || x.is_whitespace())
+                    .filter(|x| !x.is_empty())
+                    .collect();
+            }
+            for name in group.iter() {
+                if self.widget(name).is_none() {
+                    let key = if names.is_some() { key } else { "layout" };
+                    self.diagnostics
+                        .push(format!("{key}: unknown element `{name}`"));
+                }
+            }
+        }
+
+        self.polls.clear();
+        let names: BTreeSet<&str> = groups.iter().flatten().copied().collect();
+        for name in &names {
+            if let Some(poll) = poll(name, &settings) {
+                self.polls.push(poll);
+            }
+        }
+        // NOTE: polls run less often on battery power, except the one noticing the AC coming back
+        if self.on_battery {
+            for poll in &mut self.polls {
+                poll.slow_down(settings.battery_poll_factor);
+            }
+        }
+        if self.low_power {
+            for poll in &mut self.polls {
+                poll.slow_down(LOW_POWER_FACTOR);
+            }
+        }
+        let power_switching = settings.battery_profile.is_some()
+            || settings.battery_poll_factor > 1.0
+            || settings.low_power == LowPower::Auto;
+        if power_switching && !names.contains("power") {
+            self.polls
+                .push(Poll::new("power", power::COMMAND, settings.power_interval));
+        }
+        // INFO: desktop color scheme - `gsettings get org.gnome.desktop.interface color-scheme`
+        if let Some(command) = &settings.appearance_command {
+            self.polls.push(Poll::new(
+                "appearance",
+                command,
+                settings.appearance_interval,
+            ));
+        }
+
+        self.left_elements.clear();
+        self.center_elements.clear();
+        self.right_elements.clear();
+        for (index, names) in groups.iter().enumerate().take(3) {
+            let elements: Vec<_> = names.iter().filter_map(|x| self.widget(x)).collect();
+            match index {
+                0 => self.left_elements = elements,
+                1 => self.center_elements = elements,
+                _ => self.right_elements = elements,
+            }
+        }
+
+        self.settings = settings;
+        self.memo.clear();
+        self.check_contrast();
+    }
+
+    // NOTE: fg/bg pairs of the laid out elements too close to read, listed along with the
+    // configuration problems so custom palettes can be validated in place. Elements without
+    // anything to show yet are left out.
+    fn check_contrast(&mut self) {
+        if !self.settings.contrast_check {
+            self.diagnostics.set_warnings(Vec::new());
+            return;
+        }
+        let palette = &self.palette;
+        let elements = self
+            .left_elements
+            .iter()
+            .chain(&self.center_elements)
+            .chain(&self.right_elements);
+        let mut segments: Vec<(&'static str, Segment)> = Vec::new();
+        for (name, s) in elements {
+            let s = s.borrow();
+            if *name == "tabs" {
+                let active = TabInfo {
+                    active: true,
+                    ..Default::default()
+                };
+                segments.push(("active tab", Segment::new_tab(&active, palette)));
+                segments.push(("tabs", Segment::new_tab(&TabInfo::default(), palette)));
+            } else if s.is_loaded() {
+                segments.push((*name, self.theme.apply(name, s.render(palette))));
+            } else {
+                segments.push((
+                    *name,
+                    self.theme
+                        .apply(name, self.placeholders.render(name, palette)),
+                ));
+            }
+        }
+        let warnings = segments
+            .iter()
+            .filter_map(|(name, segment)| {
+                let (fg, bg) = segment.colors()?;
+                contrast::check(name, fg, bg)
+            })
+            .collect();
+        self.diagnostics.set_warnings(warnings);
+    }
+
+    // INFO: built-in elements in their historical places, optional ones enabled by their keys
+    fn default_layout(&self, settings: &Config) -> String {
+        let scope_tab = settings.scope == Scope::Tab;
+
+        let mut left = vec!["mode", "session", "workspace"];
+        if scope_tab {
+            left.push("panes");
+        }
+        left.push("context");
+        if settings.git {
+            left.push("git");
+        }
+        if !scope_tab {
+            left.push("tabs");
+        }
+
+        let mut right = vec!["custom"];
+        let optional = [
+            ("battery", settings.battery),
+            ("updates", settings.updates_check.is_some()),
+            ("auth", settings.auth),
+            ("http", settings.http_url.is_some()),
+            ("env", settings.env_vars.is_some()),
+            ("text", settings.text.is_some()),
+            ("script", settings.script.is_some()),
+            ("floating", settings.floating),
+            ("mode_usage", settings.mode_usage),
+            ("buttons", !self.buttons.borrow().is_empty()),
+        ];
+        right.extend(optional.iter().filter(|(_, x)| *x).map(|(name, _)| *name));
+        right.extend(["cmd_duration", "clock"]);
+
+        format!("{} | {}", left.join(" "), right.join(" "))
+    }
+
+    // INFO: named widgets available to `layout`
+    fn registry(&self) -> Vec<(&'static str, Shared<dyn Widget>)> {
+        vec![
+            ("mode", self.mode.widget()),
+            ("session", self.session.widget()),
+            ("workspace", self.workspace.widget()),
+            // INFO: replaces the tab list when docked in a single tab with `scope = "tab"`
+            ("panes", self.panes.widget()),
+            // INFO: announced by other plugins through `statusline::context` messages
+            ("context", self.context.widget()),
+            // INFO: rendered in place by `render` with the space left by the other elements
+            ("tabs", self.tabs.widget()),
+            ("git", self.git.widget()),
+            ("custom", self.custom.widget()),
+            ("battery", self.battery.widget()),
+            // INFO: AC or battery power, drives `battery_profile` and `battery_poll_factor`
+            ("power", self.power.widget()),
+            ("updates", self.updates.widget()),
+            ("auth", self.auth.widget()),
+            ("http", self.http.widget()),
+            // INFO: badges of the environment variables listed by `env_vars`
+            ("env", self.environment.widget()),
+            ("floating", self.floating.widget()),
+            ("buttons", self.buttons.widget()),
+            // INFO: `text` template interpolated from the state of the others
+            ("text", self.text.widget()),
+            // INFO: rhai snippet seeing the same variables as `text`
+            ("script", self.script.widget()),
+            ("cmd_duration", self.cmd.widget()),
+            // INFO: mode other than normal used the most today, tracked with `mode_usage`
+            ("mode_usage", self.usage.widget()),
+            ("clock", self.clock.widget()),
+        ]
+    }
+
+    fn widget(&self, name: &str) -> Option<(&'static str, Shared<dyn Widget>)> {
+        self.widgets.iter().find(|(x, _)| *x == name).cloned()
+    }
+
+    // INFO: no transition without animations - in low power mode - or a host to wake us up
+    fn toggle_zen(&mut self) {
+        self.zen = !self.zen;
+        // NOTE: a pinned clock would hold the transition still, so it is skipped
+        if self.headless || !segment::animations() || time::is_fixed() {
+            return;
+        }
+        self.zen_since = Some(time::now());
+        for step in 1..=ZEN_TRANSITION_STEPS {
+            set_timeout(ZEN_TRANSITION_SECONDS * step as f64 / ZEN_TRANSITION_STEPS as f64);
+        }
+    }
+
+    // INFO: part of the line drawn so far, `None` once the transition is over
+    fn zen_progress(&mut self) -> Option<f64> {
+        let elapsed = time::now() - self.zen_since?;
+        let progress = elapsed.num_milliseconds() as f64 / 1000.0 / ZEN_TRANSITION_SECONDS;
+        if progress >= 1.0 {
+            self.zen_since = None;
+            return None;
+        }
+        Some(progress.max(0.0))
+    }
+
+    // NOTE: minimal layout - mode and the active tab only
+    fn render_zen(&mut self, cols: usize, palette: &Palette) -> String {
+        let mut line = String::new();
+        let mut chars = 0;
+        for (name, s) in self.left_elements.iter().filter(|(x, _)| *x == "mode") {
+            let s = self
+                .theme
+                .apply(name, s.borrow().render(palette))
+                .to_string();
             chars += s.display_len();
+            self.regions.push((*name, 0..chars));
+            line.push_str(&s);
         }
 
-        // NOTE: render tabs
-        self.tabs.max_width = cols - chars;
-        chars += self.tabs.display_len();
-        print!("{}", self.tabs);
+        let tab = self.tabs.borrow().active().to_string();
+        chars += tab.display_len();
+        line.push_str(&tab);
 
-        // NOTE: fill empty space
         if chars < cols {
-            let fill = "-".to_string().repeat(cols - chars);
-            print!(
-                "{}{}",
-                AnsiStyle::new().fg_color(*GRAY).bg_color(*BG).render(),
-                fill
-            );
+            line.push_str(&fill(cols - chars, " ", palette));
         }
+        line
+    }
 
-        // NOTE: render right segments
-        for s in &self.right_elements {
-            print!("{s}");
+    fn element_at(&self, col: usize) -> Option<&'static str> {
+        self.regions
+            .iter()
+            .find(|(_, region)| region.contains(&col))
+            .map(|(name, _)| *name)
+    }
+
+    // NOTE: move `from` element to the position of `to`, persisting the order in the configuration
+    fn move_element(&mut self, from: &str, to: &str) -> bool {
+        for (key, elements) in [
+            (LEFT, &mut self.left_elements),
+            (CENTER, &mut self.center_elements),
+            (RIGHT, &mut self.right_elements),
+        ] {
+            let position = |name| elements.iter().position(|(x, _)| *x == name);
+            let (Some(from), Some(to)) = (position(from), position(to)) else {
+                continue;
+            };
+            if from == to {
+                return false;
+            }
+
+            let element = elements.remove(from);
+            elements.insert(to, element);
+            let order: Vec<&str> = elements.iter().map(|(name, _)| *name).collect();
+            let order = order.join(",");
+            self.config.insert(key.to_string(), order.clone());
+            self.plugin_config.insert(key.to_string(), order.clone());
+            // INFO: the new order is lost with the session, the toast tells what to configure
+            self.toast =
+                Some(i18n::tr("order_changed").replace("{setting}", &format!("{key} \"{order}\"")));
+            self.toast_expires =
+                Some(time::now() + chrono::Duration::seconds(CLIENT_TOAST_SECONDS));
+            if !self.headless {
+                set_timeout(CLIENT_TOAST_SECONDS as f64 + 0.1);
+            }
+            return true;
+        }
+        false
+    }
+
+    // INFO: what keyboard navigation moves across, in the order drawn - every tab and button on
+    // its own and the other clickable elements as a whole
+    fn targets(&self) -> Vec<(&'static str, usize)> {
+        let mut targets = Vec::new();
+        for (name, _) in &self.regions {
+            match *name {
+                "tabs" => targets.extend((0..self.tabs.borrow().count()).map(|x| (*name, x))),
+                "buttons" => targets.extend((0..self.buttons.borrow().len()).map(|x| (*name, x))),
+                "fill" if self.settings.fill_on_click.is_none() => {}
+                "updates" if self.settings.updates_command.is_none() => {}
+                _ if CLICKABLE.contains(name) => targets.push((*name, 0)),
+                _ => {}
+            }
+        }
+        targets
+    }
+
+    fn select(&mut self, target: Option<(&'static str, usize)>) {
+        self.selected = target;
+        let within = |element| target.filter(|(x, _)| *x == element).map(|(_, x)| x);
+        self.tabs.borrow_mut().select(within("tabs"));
+        self.buttons.borrow_mut().selected = within("buttons");
+    }
+
+    // INFO: element clicked without being dragged anywhere
+    fn click(&mut self, name: &str, col: usize) -> bool {
+        match name {
+            "diagnostics" => self.diagnostics.clear(),
+            "errors" => {
+                self.errors.expanded = !self.errors.expanded;
+                true
+            }
+            // INFO: switches to the clicked tab, clicking the active one applies the name suggested
+            // for it. Positions are 1-based here.
+            "tabs" => {
+                let tabs = self.tabs.borrow();
+                let offset = self
+                    .regions
+                    .iter()
+                    .find(|(x, _)| *x == "tabs")
+                    .map(|(_, region)| col - region.start);
+                let clicked = offset.and_then(|x| tabs.tab_at(self.tabs_cells, x));
+                match clicked {
+                    Some(position) if Some(position) != tabs.active_position() => {
+                        switch_tab_to(position as u32 + 1);
+                    }
+                    Some(_) => {
+                        if let Some((position, name)) = tabs.suggestion() {
+                            rename_tab(position as u32 + 1, name);
+                        }
+                    }
+                    None => {}
+                }
+                false
+            }
+            "custom" => {
+                self.history.open();
+                true
+            }
+            "floating" => {
+                buttons::run("ToggleFloatingPanes", &self.session.borrow().0);
+                false
+            }
+            "buttons" => {
+                let offset = self
+                    .regions
+                    .iter()
+                    .find(|(x, _)| *x == "buttons")
+                    .map(|(_, region)| col - region.start);
+                if let Some(action) =
+                    offset.and_then(|x| self.buttons.borrow().action_at(x).map(String::from))
+                {
+                    buttons::run(&action, &self.session.borrow().0);
+                }
+                false
+            }
+            "updates" => {
+                if let Some(command) = &self.settings.updates_command {
+                    open_command_pane(CommandToRun::new_with_args(
+                        "sh",
+                        vec!["-c", command.as_str()],
+                    ));
+                }
+                false
+            }
+            "git" => {
+                let context =
+                    BTreeMap::from([(poll::POLL.to_string(), worker::GIT_WORKTREES.to_string())]);
+                run_command(&["sh", "-c", git::WORKTREES_COMMAND], context);
+                false
+            }
+            "fill" => {
+                let action = self
+                    .settings
+                    .fill_on_click
+                    .as_deref()
+                    .and_then(MenuAction::parse);
+                match action {
+                    Some(action) => {
+                        self.request(action);
+                        true
+                    }
+                    None => false,
+                }
+            }
+            _ => false,
         }
     }
+
+    // INFO: typed characters narrow the tabs, enter focuses the first match and escape restores
+    // every tab
+    fn update_filter(&mut self, key: Key) -> bool {
+        let mut tabs = self.tabs.borrow_mut();
+        let mut query = tabs.filter().unwrap_or_default().to_string();
+        match key {
+            Key::Char('\n') => {
+                if let Some(position) = tabs.first_match() {
+                    switch_tab_to(position as u32 + 1);
+                }
+                tabs.set_filter(None);
+            }
+            Key::Esc => tabs.set_filter(None),
+            Key::Backspace => {
+                query.pop();
+                tabs.set_filter(Some(query));
+            }
+            Key::Char(c) if !c.is_control() => {
+                query.push(c);
+                tabs.set_filter(Some(query));
+            }
+            _ => return false,
+        }
+        if tabs.filter().is_none() && !self.headless {
+            set_selectable(self.wizard.is_some() || self.settings.selectable);
+        }
+        true
+    }
+
+    fn update_menu(&mut self, event: &Event) -> bool {
+        let Some(selection) = self.menu.as_ref().and_then(|x| x.handle(event)) else {
+            return false;
+        };
+
+        self.menu = None;
+        if let Selection::Picked(action) = selection {
+            self.request(action);
+        }
+        true
+    }
+
+    // INFO: destructive actions ask for a confirmation first
+    fn request(&mut self, action: MenuAction) {
+        let tabs = self.tabs.borrow();
+        let confirmation = action.confirmation(tabs.active_position(), |x| tabs.is_protected(x));
+        drop(tabs);
+        match confirmation {
+            Some(prompt) => {
+                // INFO: wake up slightly after the deadline so the prompt is surely expired by then
+                if !self.headless {
+                    set_timeout(CONFIRM_SECONDS + 0.1);
+                }
+                self.confirm = Some(Confirm::new(prompt, action, CONFIRM_SECONDS));
+            }
+            None => self.perform(action),
+        }
+    }
+
+    fn update_confirm(&mut self, event: &Event) -> bool {
+        let Some(selection) = self.confirm.as_ref().and_then(|x| x.handle(event)) else {
+            return false;
+        };
+
+        self.confirm = None;
+        if let Selection::Picked(action) = selection {
+            self.perform(action);
+        }
+        true
+    }
+
+    fn perform(&mut self, action: MenuAction) {
+        match action {
+            MenuAction::NewTab => new_tab(),
+            MenuAction::Detach => detach(),
+            MenuAction::Lock => switch_to_input_mode(&InputMode::Locked),
+            MenuAction::Zen => self.toggle_zen(),
+            MenuAction::CloseTab => {
+                // INFO: protected tabs can't be closed from the bar
+                let tabs = self.tabs.borrow();
+                let protected = tabs.active_position().is_some_and(|x| tabs.is_protected(x));
+                if !protected {
+                    close_focused_tab();
+                }
+            }
+            MenuAction::ClearNotifications => {
+                self.custom.borrow_mut().clear();
+            }
+            MenuAction::Quit => quit_zellij(),
+            MenuAction::SessionManager => start_or_reload_plugin("zellij:session-manager"),
+            // INFO: layouts passed by plugins don't get the default tab template, the new tab
+            // has a single pane
+            MenuAction::OpenWorktree { path, branch } => {
+                new_tabs_with_layout(&MenuAction::worktree_layout(&path, &branch))
+            }
+        }
+    }
+
+    fn update_wizard(&mut self, event: Event) -> bool {
+        let Some(wizard) = &mut self.wizard else {
+            return false;
+        };
+        let next_step = match event {
+            Event::Key(Key::Right) | Event::Mouse(Mouse::LeftClick(..)) => {
+                wizard.next_option();
+                false
+            }
+            Event::Key(Key::Left) => {
+                wizard.previous_option();
+                false
+            }
+            Event::Key(Key::Char('\n')) | Event::Mouse(Mouse::RightClick(..)) => true,
+            Event::Key(Key::Esc) => {
+                Wizard::finish();
+                self.wizard = None;
+                self.build();
+                return true;
+            }
+            _ => return false,
+        };
+
+        if next_step && wizard.next_step() {
+            // INFO: apply the choices right away and dump them as a snippet for the layout file
+            let choices = wizard.config();
+            Wizard::finish();
+            self.wizard = None;
+            let dumped = config::dump(&choices);
+            self.plugin_config = choices;
+            self.reload();
+            if let Err(err) = dumped {
+                self.toast =
+                    Some(i18n::tr("config_dump_error").replace("{error}", &err.to_string()));
+            }
+        }
+        true
+    }
+}
+
+// INFO: commands and requests feeding the elements, run only for elements in the layout
+fn poll(name: &str, settings: &Config) -> Option<Poll> {
+    let poll = match name {
+        // INFO: filled by a polled command, empty until the first result arrives
+        "battery" => {
+            let command = settings
+                .battery_command
+                .as_deref()
+                .unwrap_or(battery::DEFAULT_COMMAND);
+            Poll::new("battery", command, settings.battery_interval)
+        }
+        // INFO: pending package updates counted by `updates_check`, once a day by default
+        "updates" => Poll::new(
+            "updates",
+            settings.updates_check.as_deref()?,
+            settings.updates_interval,
+        ),
+        // INFO: recent failed logins, meant for servers running long-lived sessions
+        "auth" => {
+            let check = settings
+                .auth_check
+                .as_deref()
+                .unwrap_or(auth::DEFAULT_COMMAND);
+            Poll::new("auth", check, settings.auth_interval)
+        }
+        // INFO: value extracted from a JSON endpoint, colored by `http_colors`
+        "http" if !compat::web_requests() => return None,
+        "http" => Poll::url(
+            "http",
+            settings.http_url.as_deref()?,
+            settings.http_interval,
+        ),
+        "power" => Poll::new("power", power::COMMAND, settings.power_interval),
+        // INFO: the environment of the zellij server, rarely changes
+        "env" => {
+            settings.env_vars.as_ref()?;
+            Poll::new("env", environment::COMMAND, settings.env_interval)
+        }
+        // INFO: branch of the repository zellij was started in, click lists its worktrees
+        "git" => Poll::new("git", git::BRANCH_COMMAND, settings.git_interval),
+        _ => return None,
+    };
+    Some(poll)
 }
 
-trait DisplayExt {
-    fn display_len(&self) -> usize;
+#[derive(Clone)]
+pub enum MenuAction {
+    NewTab,
+    Detach,
+    Lock,
+    Zen,
+    CloseTab,
+    ClearNotifications,
+    Quit,
+    SessionManager,
+    OpenWorktree { path: String, branch: String },
 }
 
-impl<T: Display> DisplayExt for T {
-    fn display_len(&self) -> usize {
-        strip_ansi_escapes::strip_str(self.to_string())
-            .graphemes(true)
-            .count()
+impl MenuAction {
+    // INFO: actions bound through the configuration - `fill_on_click = "new_tab"`
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "new_tab" => Some(MenuAction::NewTab),
+            "detach" => Some(MenuAction::Detach),
+            "lock" => Some(MenuAction::Lock),
+            "zen" => Some(MenuAction::Zen),
+            "close_tab" => Some(MenuAction::CloseTab),
+            "clear_notifications" => Some(MenuAction::ClearNotifications),
+            "quit" => Some(MenuAction::Quit),
+            "session_manager" => Some(MenuAction::SessionManager),
+            _ => None,
+        }
+    }
+
+    // INFO: prompt of destructive actions which have to be confirmed first
+    fn confirmation(
+        &self,
+        active_tab: Option<usize>,
+        protected: impl Fn(usize) -> bool,
+    ) -> Option<String> {
+        match self {
+            MenuAction::CloseTab if active_tab.is_some_and(&protected) => None,
+            MenuAction::CloseTab => {
+                let tab = active_tab.map(|x| (x + 1).to_string()).unwrap_or_default();
+                Some(i18n::tr("confirm_close_tab").replace("{tab}", &tab))
+            }
+            MenuAction::ClearNotifications => Some(i18n::tr("confirm_clear_notifications")),
+            MenuAction::Quit => Some(i18n::tr("confirm_quit")),
+            _ => None,
+        }
+    }
+
+    // INFO: `layout { tab name="<branch>" cwd="<path>" { pane } }`, quoted by the KDL serializer
+    fn worktree_layout(path: &str, branch: &str) -> String {
+        let mut tab = KdlNode::new("tab");
+        tab.insert("name", branch);
+        tab.insert("cwd", path);
+        let mut panes = KdlDocument::new();
+        panes.nodes_mut().push(KdlNode::new("pane"));
+        tab.set_children(panes);
+        let mut tabs = KdlDocument::new();
+        tabs.nodes_mut().push(tab);
+        let mut layout = KdlNode::new("layout");
+        layout.set_children(tabs);
+        let mut document = KdlDocument::new();
+        document.nodes_mut().push(layout);
+        KdlDocument::fmt(&mut document);
+        document.to_string()
     }
 }
 
+// NOTE: input mode along with the key of the normal mode leading to it, set when
+// `mode_breadcrumb` is enabled
 #[derive(Default, PartialEq, Clone, Copy)]
-pub struct Mode(InputMode);
+pub struct Mode(InputMode, Option<Key>, bool);
 
 impl PartialEq<InputMode> for Mode {
     fn eq(&self, other: &InputMode) -> bool {
@@ -186,41 +2155,175 @@ impl PartialEq<InputMode> for Mode {
 impl Display for Mode {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let repr = match self.0 {
-            InputMode::Normal => "NORMAL",
-            InputMode::Locked => "LOCKED",
-            InputMode::Resize => "RESIZE",
-            InputMode::Pane => "PANE",
-            InputMode::Tab => "TAB",
-            InputMode::Scroll => "SCROLL",
-            InputMode::EnterSearch => "SEARCH",
-            InputMode::Search => "SEARCH",
-            InputMode::RenameTab => "TAB",
-            InputMode::RenamePane => "PANE",
-            InputMode::Session => "SESSION",
-            InputMode::Move => "MOVE",
-            InputMode::Prompt => "PROMPT",
-            InputMode::Tmux => "TMUX",
+            InputMode::Normal => "mode_normal",
+            InputMode::Locked => "mode_locked",
+            InputMode::Resize => "mode_resize",
+            InputMode::Pane => "mode_pane",
+            InputMode::Tab => "mode_tab",
+            InputMode::Scroll => "mode_scroll",
+            InputMode::EnterSearch => "mode_search",
+            InputMode::Search => "mode_search",
+            InputMode::RenameTab => "mode_tab",
+            InputMode::RenamePane => "mode_pane",
+            InputMode::Session => "mode_session",
+            InputMode::Move => "mode_move",
+            InputMode::Prompt => "mode_prompt",
+            InputMode::Tmux => "mode_tmux",
+        };
+        let label = i18n::tr(repr);
+        if nerd_fonts() {
+            write!(f, "{label}")
+        } else {
+            write!(f, "{}", accessible::strip_glyphs(&label))
+        }
+    }
+}
+
+impl Widget for Mode {
+    fn interested_events(&self) -> Vec<EventType> {
+        vec![EventType::ModeUpdate]
+    }
+
+    fn update(&mut self, event: &Event) -> bool {
+        let Event::ModeUpdate(mode_info) = event else {
+            return false;
         };
-        write!(f, "{repr}")
+        let prefix = if self.2 { prefix(mode_info) } else { None };
+        // INFO: zellij sends mode updates for more than mode switches
+        let changed = (self.0, self.1) != (mode_info.mode, prefix);
+        self.0 = mode_info.mode;
+        self.1 = prefix;
+        changed
+    }
+
+    fn render(&self, palette: &Palette) -> Segment {
+        let content: Box<dyn Display> = match self.1 {
+            Some(key) => Box::new(format!("{self} {} …", short_key(key))),
+            None => Box::new(*self),
+        };
+        Segment::new(
+            content,
+            AnsiStyle::new()
+                .bg_color(self.color(palette))
+                .fg_color(palette.black)
+                .bold(),
+            palette,
+        )
+        .min_width(10)
+        .priority(90)
+    }
+
+    fn state(&self) -> Option<u64> {
+        Some(memo::hash(&(self.0, self.1)))
     }
 }
 
 impl Mode {
-    fn color(&self) -> Option<Color> {
+    pub fn set_breadcrumb(&mut self, enabled: bool) {
+        self.2 = enabled;
+        if !enabled {
+            self.1 = None;
+        }
+    }
+
+    fn color(&self, palette: &Palette) -> Option<Color> {
         match self.0 {
-            InputMode::Normal => *BLUE,
-            InputMode::Locked => *GRAY,
-            InputMode::Tmux => *RED,
-            InputMode::Scroll | InputMode::EnterSearch | InputMode::Search => *MAGENTA,
-            _ => *YELLOW,
+            InputMode::Normal => palette.blue,
+            InputMode::Locked => palette.gray,
+            InputMode::Tmux => palette.red,
+            InputMode::Scroll | InputMode::EnterSearch | InputMode::Search => palette.magenta,
+            _ => palette.yellow,
         }
     }
 }
 
+// INFO: key switching from the normal mode to the current one - `Ctrl+b` for tmux
+fn prefix(mode_info: &ModeInfo) -> Option<Key> {
+    if matches!(mode_info.mode, InputMode::Normal | InputMode::Locked) {
+        return None;
+    }
+    mode_info
+        .get_keybinds_for_mode(InputMode::Normal)
+        .into_iter()
+        .find(|(_, x)| x.contains(&actions::Action::SwitchToMode(mode_info.mode)))
+        .map(|(key, _)| key)
+}
+
+// INFO: emacs notation - `C-b`, `M-n`
+fn short_key(key: Key) -> String {
+    match key {
+        Key::Ctrl(c) => format!("C-{c}"),
+        Key::Alt(c) => format!("M-{c}"),
+        key => key.to_string(),
+    }
+}
+
+pub struct Session(String);
+
+impl Default for Session {
+    fn default() -> Self {
+        Self("default".into())
+    }
+}
+
+impl Widget for Session {
+    fn interested_events(&self) -> Vec<EventType> {
+        vec![EventType::SessionUpdate]
+    }
+
+    fn update(&mut self, event: &Event) -> bool {
+        let Some(sessions) = compat::sessions(event) else {
+            return false;
+        };
+        let Some(session) = sessions.iter().find(|x| x.is_current_session) else {
+            return false;
+        };
+        let name = sanitize::text(&session.name);
+        let changed = self.0 != name;
+        self.0 = name;
+        changed
+    }
+
+    fn render(&self, palette: &Palette) -> Segment {
+        Segment::new(
+            Box::new(self.0.clone()),
+            AnsiStyle::new()
+                .bg_color(palette.green)
+                .fg_color(palette.black),
+            palette,
+        )
+        .min_width(10)
+        .priority(20)
+    }
+
+    fn state(&self) -> Option<u64> {
+        Some(memo::hash(&self.0))
+    }
+}
+
+// NOTE: text and style set through `statusline::context` messages
+#[derive(Default)]
+pub struct Context(Option<(String, AnsiStyle)>);
+
+impl Widget for Context {
+    fn render(&self, palette: &Palette) -> Segment {
+        let Some((text, style)) = &self.0 else {
+            return Segment::empty();
+        };
+        Segment::new(Box::new(text.clone()), *style, palette)
+    }
+}
+
+impl Context {
+    fn is_empty(&self) -> bool {
+        self.0.is_none()
+    }
+}
+
 #[derive(Default)]
-pub struct DisplayRefCell<T>(RefCell<T>);
+pub struct DisplayRefCell<T: ?Sized>(RefCell<T>);
 
-impl<T> Deref for DisplayRefCell<T> {
+impl<T: ?Sized> Deref for DisplayRefCell<T> {
     type Target = RefCell<T>;
 
     fn deref(&self) -> &Self::Target {
@@ -244,9 +2347,15 @@ impl<T> DisplayRefCell<T> {
 }
 
 #[derive(Default)]
-pub struct Shared<T>(Rc<DisplayRefCell<T>>);
+pub struct Shared<T: ?Sized>(Rc<DisplayRefCell<T>>);
 
-impl<T> Deref for Shared<T> {
+impl<T: ?Sized> Clone for Shared<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T: ?Sized> Deref for Shared<T> {
     type Target = Rc<DisplayRefCell<T>>;
 
     fn deref(&self) -> &Self::Target {
@@ -254,17 +2363,15 @@ impl<T> Deref for Shared<T> {
     }
 }
 
-impl<T> Display for Shared<T>
-where
-    T: Display,
-{
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.0.borrow())
-    }
-}
-
 impl<T> Shared<T> {
     fn new(inner: T) -> Self {
         Self(Rc::new(DisplayRefCell::new(inner)))
     }
 }
+
+impl<T: Widget + 'static> Shared<T> {
+    // INFO: same widget behind a type-erased handle, as kept by the registry
+    fn widget(&self) -> Shared<dyn Widget> {
+        Shared(self.0.clone())
+    }
+}
@@ -0,0 +1,81 @@
+use std::{
+    cell::RefCell,
+    collections::{hash_map::DefaultHasher, BTreeMap},
+    hash::{Hash, Hasher},
+};
+
+use crate::{widget::Widget, Palette, Segment};
+
+// NOTE: segments of widgets declaring their input state, rendered again only once it changes.
+// Segments capture the palette and the separators, `clear` on every (re)build and palette switch.
+#[derive(Default)]
+pub struct Memo {
+    entries: RefCell<BTreeMap<&'static str, (u64, Segment)>>,
+}
+
+impl Memo {
+    // INFO: widgets without a state are rendered every time
+    pub fn render(&self, name: &'static str, widget: &dyn Widget, palette: &Palette) -> Segment {
+        let Some(state) = widget.state() else {
+            return widget.render(palette);
+        };
+        let mut entries = self.entries.borrow_mut();
+        match entries.get(name) {
+            Some((previous, segment)) if *previous == state => segment.clone(),
+            _ => {
+                let segment = widget.render(palette);
+                entries.insert(name, (state, segment.clone()));
+                segment
+            }
+        }
+    }
+
+    pub fn clear(&self) {
+        self.entries.borrow_mut().clear();
+    }
+}
+
+pub fn hash(value: &impl Hash) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use anstyle::Style as AnsiStyle;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct Counter {
+        state: u64,
+        renders: Cell<usize>,
+    }
+
+    impl Widget for Counter {
+        fn render(&self, palette: &Palette) -> Segment {
+            self.renders.set(self.renders.get() + 1);
+            Segment::new(Box::new(self.state), AnsiStyle::new(), palette)
+        }
+
+        fn state(&self) -> Option<u64> {
+            Some(self.state)
+        }
+    }
+
+    #[test]
+    fn renders_again_on_state_change() {
+        let memo = Memo::default();
+        let mut counter = Counter::default();
+        memo.render("counter", &counter, &Palette::default());
+        let cached = memo.render("counter", &counter, &Palette::default());
+        assert_eq!(counter.renders.get(), 1);
+        counter.state = 1;
+        let rendered = memo.render("counter", &counter, &Palette::default());
+        assert_eq!(counter.renders.get(), 2);
+        assert_ne!(cached.to_string(), rendered.to_string());
+    }
+}
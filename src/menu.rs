@@ -0,0 +1,86 @@
+use std::ops::Range;
+
+use anstyle::Style as AnsiStyle;
+use zellij_tile::prelude::*;
+
+use crate::{DisplayExt, Palette, Segment};
+
+// NOTE: transient one-line menu replacing the bar until an item is picked or it's dismissed
+pub struct Menu<A> {
+    title: String,
+    items: Vec<(String, A)>,
+    regions: Vec<Range<usize>>,
+}
+
+pub enum Selection<A> {
+    Picked(A),
+    Dismissed,
+}
+
+impl<A: Clone> Menu<A> {
+    pub fn new(title: &str, items: Vec<(String, A)>) -> Self {
+        Self {
+            title: title.to_string(),
+            items,
+            regions: vec![],
+        }
+    }
+
+    // INFO: items are numbered so they can be picked with number keys as well
+    pub fn render(&mut self, palette: &Palette) -> String {
+        let mut line = Segment::new(
+            Box::new(self.title.clone()),
+            AnsiStyle::new()
+                .fg_color(palette.black)
+                .bg_color(palette.yellow)
+                .bold(),
+            palette,
+        )
+        .to_string();
+        let mut chars = line.display_len();
+
+        self.regions.clear();
+        for (index, (label, _)) in self.items.iter().enumerate() {
+            let item = Segment::new(
+                Box::new(format!("{} {label}", index + 1)),
+                AnsiStyle::new()
+                    .fg_color(palette.black)
+                    .bg_color(palette.gray),
+                palette,
+            )
+            .to_string();
+            let width = item.display_len();
+            self.regions.push(chars..chars + width);
+            chars += width;
+            line += &item;
+        }
+        line
+    }
+
+    // INFO: the menu is drawn `offset` columns into the pane, past the left margin
+    pub fn shift(&mut self, offset: usize) {
+        for region in &mut self.regions {
+            *region = region.start + offset..region.end + offset;
+        }
+    }
+
+    // INFO: `None` when the event isn't meant for the menu
+    pub fn handle(&self, event: &Event) -> Option<Selection<A>> {
+        let index = match event {
+            Event::Key(Key::Esc) | Event::Mouse(Mouse::RightClick(..)) => {
+                return Some(Selection::Dismissed)
+            }
+            Event::Key(Key::Char(c)) => c.to_digit(10)?.checked_sub(1)? as usize,
+            Event::Mouse(Mouse::LeftClick(_, col)) => {
+                match self.regions.iter().position(|x| x.contains(col)) {
+                    Some(index) => index,
+                    None => return Some(Selection::Dismissed),
+                }
+            }
+            _ => return None,
+        };
+
+        let (_, action) = self.items.get(index)?;
+        Some(Selection::Picked(action.clone()))
+    }
+}
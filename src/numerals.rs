@@ -0,0 +1,87 @@
+use std::str::FromStr;
+
+// NOTE: digit grouping and native digits of a locale selected by `numeral_locale`, `none` keeps
+// numbers as they are
+#[derive(Default, Clone, Copy, PartialEq)]
+pub struct Numerals {
+    separator: Option<char>,
+    // INFO: lakh grouping - `12,34,567`
+    indian: bool,
+    digits: Option<[char; 10]>,
+}
+
+const ARABIC: [char; 10] = ['٠', '١', '٢', '٣', '٤', '٥', '٦', '٧', '٨', '٩'];
+const PERSIAN: [char; 10] = ['۰', '۱', '۲', '۳', '۴', '۵', '۶', '۷', '۸', '۹'];
+const DEVANAGARI: [char; 10] = ['०', '१', '२', '३', '४', '५', '६', '७', '८', '९'];
+const BENGALI: [char; 10] = ['০', '১', '২', '৩', '৪', '৫', '৬', '৭', '৮', '৯'];
+
+impl FromStr for Numerals {
+    type Err = String;
+
+    fn from_str(locale: &str) -> Result<Self, Self::Err> {
+        let (separator, indian, digits) = match locale {
+            "none" => (None, false, None),
+            "en" => (Some(','), false, None),
+            "de" => (Some('.'), false, None),
+            "ch" => (Some('\''), false, None),
+            "fr" => (Some('\u{202f}'), false, None),
+            "pl" => (Some('\u{a0}'), false, None),
+            "hi" => (Some(','), true, Some(DEVANAGARI)),
+            "bn" => (Some(','), true, Some(BENGALI)),
+            "ar" => (Some('٬'), false, Some(ARABIC)),
+            "fa" => (Some('٬'), false, Some(PERSIAN)),
+            _ => return Err("expected none, en, de, ch, fr, pl, hi, bn, ar or fa".to_string()),
+        };
+        Ok(Self {
+            separator,
+            indian,
+            digits,
+        })
+    }
+}
+
+impl Numerals {
+    // INFO: groups the integer part of a plain number - `-1234567.5` -> `-1,234,567.5`
+    pub fn group(&self, number: &str) -> String {
+        let Some(separator) = self.separator else {
+            return number.to_string();
+        };
+        let start = number.find(|x: char| x.is_ascii_digit()).unwrap_or(0);
+        let end = number[start..]
+            .find(|x: char| !x.is_ascii_digit())
+            .map_or(number.len(), |x| start + x);
+        let integer = &number[start..end];
+
+        let mut grouped = String::new();
+        for (index, digit) in integer.chars().enumerate() {
+            let left = integer.len() - index;
+            let boundary = if self.indian {
+                left >= 3 && left % 2 == 1
+            } else {
+                left.is_multiple_of(3)
+            };
+            if index > 0 && boundary {
+                grouped.push(separator);
+            }
+            grouped.push(digit);
+        }
+        format!("{}{grouped}{}", &number[..start], &number[end..])
+    }
+
+    // INFO: every ASCII digit of `text` in the native script
+    pub fn digits(&self, text: &str) -> String {
+        let Some(digits) = self.digits else {
+            return text.to_string();
+        };
+        text.chars()
+            .map(|x| match x.to_digit(10) {
+                Some(digit) if x.is_ascii_digit() => digits[digit as usize],
+                _ => x,
+            })
+            .collect()
+    }
+
+    pub fn format(&self, number: &str) -> String {
+        self.digits(&self.group(number))
+    }
+}
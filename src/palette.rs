@@ -0,0 +1,75 @@
+use anstyle::{AnsiColor, Color, RgbColor};
+
+// NOTE: colors of the bar, built from the theme on (re)load and passed to every render. Variants
+// of it - dimmed placeholders, the locked bar - are rendered next to the regular one.
+#[derive(Clone, Copy, PartialEq)]
+pub struct Palette {
+    pub bg: Option<Color>,
+    pub red: Option<Color>,
+    pub green: Option<Color>,
+    pub yellow: Option<Color>,
+    pub blue: Option<Color>,
+    pub magenta: Option<Color>,
+    pub cyan: Option<Color>,
+    pub gray: Option<Color>,
+    pub white: Option<Color>,
+    pub black: Option<Color>,
+}
+
+const DEFAULT_PALETTE: Palette = Palette {
+    bg: Some(Color::Ansi(AnsiColor::Black)),
+    red: Some(Color::Ansi(AnsiColor::Red)),
+    green: Some(Color::Ansi(AnsiColor::Green)),
+    yellow: Some(Color::Ansi(AnsiColor::Yellow)),
+    blue: Some(Color::Ansi(AnsiColor::Blue)),
+    magenta: Some(Color::Ansi(AnsiColor::Magenta)),
+    cyan: Some(Color::Ansi(AnsiColor::Cyan)),
+    gray: Some(Color::Ansi(AnsiColor::White)),
+    white: Some(Color::Ansi(AnsiColor::BrightWhite)),
+    black: Some(Color::Rgb(RgbColor(0, 0, 0))),
+};
+
+impl Default for Palette {
+    fn default() -> Self {
+        DEFAULT_PALETTE
+    }
+}
+
+impl Palette {
+    // INFO: colors halfway to the background, or the gray ones where they can't be mixed -
+    // placeholders of elements still loading
+    pub fn dimmed(&self) -> Palette {
+        let mix = |color: Option<Color>, fallback: Option<Color>| match (color, self.bg) {
+            (Some(Color::Rgb(x)), Some(Color::Rgb(bg))) => Some(Color::Rgb(RgbColor(
+                x.0 / 2 + bg.0 / 2,
+                x.1 / 2 + bg.1 / 2,
+                x.2 / 2 + bg.2 / 2,
+            ))),
+            _ => fallback,
+        };
+        Palette {
+            red: mix(self.red, self.gray),
+            green: mix(self.green, self.gray),
+            yellow: mix(self.yellow, self.gray),
+            blue: mix(self.blue, self.gray),
+            magenta: mix(self.magenta, self.gray),
+            cyan: mix(self.cyan, self.gray),
+            gray: mix(self.gray, Some(Color::Ansi(AnsiColor::BrightBlack))),
+            white: mix(self.white, self.gray),
+            ..*self
+        }
+    }
+
+    // INFO: accents turn gray, the mode stays readable without the colors telling it apart
+    pub fn locked(&self) -> Palette {
+        Palette {
+            red: self.gray,
+            green: self.gray,
+            yellow: self.gray,
+            blue: self.gray,
+            magenta: self.gray,
+            cyan: self.gray,
+            ..*self
+        }
+    }
+}
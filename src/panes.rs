@@ -0,0 +1,80 @@
+use anstyle::Style as AnsiStyle;
+use zellij_tile::prelude::*;
+
+use crate::{glyph, sanitize, segment::tab_index, widget::Widget, Palette, Segment};
+
+// NOTE: `scope = "tab"` - the plugin docked in a tab layout reports the panes of its own tab
+// instead of every tab. Zellij doesn't expose pane working directories, the focused pane is
+// described by its running command or title (which most shells set to the cwd).
+#[derive(Default)]
+pub struct Panes {
+    plugin_id: u32,
+    tab: Option<usize>,
+    count: usize,
+    focused: Option<String>,
+}
+
+impl Panes {
+    pub fn new(plugin_id: u32) -> Self {
+        Self {
+            plugin_id,
+            ..Self::default()
+        }
+    }
+}
+
+impl Widget for Panes {
+    fn interested_events(&self) -> Vec<EventType> {
+        vec![EventType::PaneUpdate]
+    }
+
+    // INFO: returns `true` when anything shown changed
+    fn update(&mut self, event: &Event) -> bool {
+        let Event::PaneUpdate(manifest) = event else {
+            return false;
+        };
+        let own = manifest
+            .panes
+            .iter()
+            .find(|(_, panes)| panes.iter().any(|x| x.is_plugin && x.id == self.plugin_id));
+        let Some((tab, panes)) = own else {
+            return false;
+        };
+        let terminals: Vec<&PaneInfo> = panes
+            .iter()
+            .filter(|x| !x.is_plugin && !x.is_suppressed)
+            .collect();
+        // INFO: titles are set by whatever runs in the pane
+        let focused = terminals
+            .iter()
+            .find(|x| x.is_focused)
+            .map(|x| sanitize::text(x.terminal_command.as_deref().unwrap_or(&x.title)));
+
+        let changed =
+            (Some(*tab), terminals.len(), &focused) != (self.tab, self.count, &self.focused);
+        self.tab = Some(*tab);
+        self.count = terminals.len();
+        self.focused = focused;
+        changed
+    }
+
+    fn render(&self, palette: &Palette) -> Segment {
+        let Some(tab) = self.tab else {
+            return Segment::empty();
+        };
+        let focused = self.focused.as_deref().unwrap_or_default();
+        Segment::new(
+            Box::new(format!(
+                "{}  {focused} {} {}",
+                tab_index(tab),
+                glyph("󰕰", "panes"),
+                self.count
+            )),
+            AnsiStyle::new()
+                .fg_color(palette.black)
+                .bg_color(palette.yellow),
+            palette,
+        )
+        .max_width(64)
+    }
+}
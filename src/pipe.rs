@@ -0,0 +1,281 @@
+use serde::Deserialize;
+use zellij_tile::prelude::*;
+
+use crate::{compat, sanitize};
+
+// NOTE: version of the JSON envelope protocol, bumped on incompatible changes
+pub const PROTOCOL_VERSION: u32 = 1;
+
+// NOTE: piped segments expire after a day at most
+const MAX_TTL: f64 = 86400.0;
+
+// NOTE: Pipe message names
+pub const STATUSLINE: &str = "statusline";
+pub const DUMP_CONFIG: &str = "statusline::dump-config";
+pub const ZEN: &str = "statusline::zen";
+// INFO: toggles reordering elements by dragging them, meant for an `Alt` key binding - zellij
+// doesn't pass the modifiers held during mouse events to plugins
+pub const ARRANGE: &str = "statusline::arrange";
+// INFO: payload is the segment name - `mode`, `session`, `workspace`, `context`, `custom`, `tabs`, `clock`
+pub const TOGGLE: &str = "statusline::toggle";
+// INFO: payload is the tab name, the active tab is toggled without it
+pub const PROTECT: &str = "statusline::protect";
+// INFO: opens the viewer of recently piped notifications
+pub const HISTORY: &str = "statusline::history";
+// INFO: reads `config_file` and the zellij theme file again
+pub const RELOAD: &str = "statusline::reload";
+// INFO: starts or stops recording received events for bug reports
+pub const RECORD: &str = "statusline::record";
+// INFO: focuses the bar and turns the tabs into a filter typed into
+pub const FILTER: &str = "statusline::filter";
+// INFO: asks for the permissions again after they were denied
+pub const PERMISSIONS: &str = "statusline::permissions";
+// INFO: replies with the time spent in each input mode today and over the last week
+pub const MODE_USAGE: &str = "statusline::mode-usage";
+
+// NOTE: contract for other plugins announcing their context (e.g. the active project):
+// - message name: `statusline::context`
+// - payload: text to show, a missing or empty payload clears the segment
+// - args: optional `fg` and `bg` colors (same format as color config values)
+pub const CONTEXT: &str = "statusline::context";
+
+// NOTE: contract for shell hooks reporting command durations:
+// - message name: `statusline::cmd`
+// - payload: `start` before a command runs (preexec), `end` once it finished (precmd)
+// - args: `pane` - `$ZELLIJ_PANE_ID`, optional `at` - epoch seconds (`$EPOCHREALTIME`),
+//   optional `exit` - exit status of the finished command
+// e.g. `zellij pipe --name statusline::cmd --args "pane=$ZELLIJ_PANE_ID,exit=$status" -- end`
+pub const CMD: &str = "statusline::cmd";
+
+// NOTE: contract for job watchers reporting progress:
+// - message name: `statusline::progress`
+// - payload: percentage done, `100`, `done` or a missing payload clears the bar
+// - args: optional `tab` - name of the tab running the job, the active one otherwise
+// e.g. `zellij pipe --name statusline::progress --args "tab=build" -- 42`
+pub const PROGRESS: &str = "statusline::progress";
+
+// INFO: `{"v":1,"target":"segment","name":"cpu","text":"83%","fg":"red","ttl":5}`, plain text
+// payloads are commands for scripts instead - `set cpu '83 %'`, `remove cpu`, `hide cpu`, `show cpu`
+#[derive(Deserialize)]
+pub struct Envelope {
+    #[serde(default = "protocol_version")]
+    pub v: u32,
+    pub target: String,
+    pub name: String,
+    pub text: Option<String>,
+    pub fg: Option<String>,
+    pub bg: Option<String>,
+    pub ttl: Option<f64>,
+    // INFO: numeric sample for the sparkline history of the segment
+    pub value: Option<f64>,
+    // INFO: human readable format of `value` - `bytes`, `rate`, `duration` or `number`,
+    // substituted for `{value}` in `text` or used as the whole text when it's missing
+    pub format: Option<String>,
+}
+
+fn protocol_version() -> u32 {
+    PROTOCOL_VERSION
+}
+
+pub enum Request {
+    DumpConfig,
+    Zen,
+    Arrange,
+    Toggle(String),
+    Protect(Option<String>),
+    History,
+    Reload,
+    Record,
+    Filter,
+    Permissions,
+    ModeUsage,
+    // INFO: piped segment or element of the bar by name
+    Visibility {
+        name: String,
+        hidden: bool,
+    },
+    // INFO: segment without `text` and `value` is removed
+    Segment(Envelope),
+    Context {
+        text: Option<String>,
+        fg: Option<String>,
+        bg: Option<String>,
+    },
+    Progress {
+        tab: Option<String>,
+        percent: Option<f64>,
+    },
+    Cmd {
+        pane: u32,
+        finished: bool,
+        at: Option<f64>,
+        exit: Option<i32>,
+    },
+}
+
+// INFO: every text ending up in the bar is sanitized and cut at `max_width` cells here
+pub fn dispatch(message: &PipeMessage, max_width: usize) -> Result<Option<Request>, String> {
+    let clean = |text: &str| sanitize::payload(text, max_width);
+    match message.name.as_str() {
+        DUMP_CONFIG => Ok(Some(Request::DumpConfig)),
+        ZEN => Ok(Some(Request::Zen)),
+        ARRANGE => Ok(Some(Request::Arrange)),
+        HISTORY => Ok(Some(Request::History)),
+        RELOAD => Ok(Some(Request::Reload)),
+        RECORD => Ok(Some(Request::Record)),
+        FILTER => Ok(Some(Request::Filter)),
+        PERMISSIONS => Ok(Some(Request::Permissions)),
+        MODE_USAGE => Ok(Some(Request::ModeUsage)),
+        TOGGLE => match message.payload.as_deref().map(str::trim) {
+            Some(name) if !name.is_empty() => Ok(Some(Request::Toggle(clean(name)))),
+            _ => Err("missing segment name".to_string()),
+        },
+        PROTECT => Ok(Some(Request::Protect(
+            message
+                .payload
+                .as_deref()
+                .map(clean)
+                .filter(|x| !x.is_empty()),
+        ))),
+        CONTEXT => Ok(Some(Request::Context {
+            text: message
+                .payload
+                .as_deref()
+                .map(clean)
+                .filter(|x| !x.is_empty()),
+            fg: message.args.get("fg").cloned(),
+            bg: message.args.get("bg").cloned(),
+        })),
+        PROGRESS => {
+            let percent = match message.payload.as_deref().map(str::trim) {
+                None | Some("" | "done") => None,
+                Some(percent) => Some(
+                    percent
+                        .trim_end_matches('%')
+                        .parse::<f64>()
+                        .ok()
+                        .filter(|x| x.is_finite())
+                        .ok_or("payload has to be a percentage or `done`")?,
+                ),
+            };
+            Ok(Some(Request::Progress {
+                tab: message.args.get("tab").cloned(),
+                percent: percent.filter(|x| *x < 100.0),
+            }))
+        }
+        CMD => {
+            let finished = match message.payload.as_deref().map(str::trim) {
+                Some("start") => false,
+                Some("end") => true,
+                _ => return Err("payload has to be `start` or `end`".to_string()),
+            };
+            let pane = message
+                .args
+                .get("pane")
+                .and_then(|x| x.parse().ok())
+                .ok_or("missing or invalid `pane` argument")?;
+            Ok(Some(Request::Cmd {
+                pane,
+                finished,
+                at: message.args.get("at").and_then(|x| x.parse().ok()),
+                exit: message.args.get("exit").and_then(|x| x.parse().ok()),
+            }))
+        }
+        STATUSLINE => {
+            let payload = message.payload.as_deref().ok_or("missing payload")?;
+            if !payload.trim_start().starts_with('{') {
+                return command(payload, clean).map(Some);
+            }
+            let mut envelope: Envelope =
+                serde_json::from_str(payload).map_err(|err| format!("invalid payload: {err}"))?;
+            envelope.name = clean(&envelope.name);
+            envelope.text = envelope.text.as_deref().map(clean);
+            if envelope.v > PROTOCOL_VERSION {
+                return Err(format!(
+                    "unsupported protocol version {} (supported: {PROTOCOL_VERSION})",
+                    envelope.v
+                ));
+            }
+            if envelope.ttl.is_some_and(|x| !x.is_finite() || x < 0.0) {
+                return Err("`ttl` has to be zero or more seconds".to_string());
+            }
+            envelope.ttl = envelope.ttl.map(|x| x.min(MAX_TTL));
+
+            match envelope.target.as_str() {
+                "segment" => Ok(Some(Request::Segment(envelope))),
+                "command" => match envelope.name.as_str() {
+                    "zen" => Ok(Some(Request::Zen)),
+                    "dump-config" => Ok(Some(Request::DumpConfig)),
+                    "reload" => Ok(Some(Request::Reload)),
+                    name => Err(format!("unknown command `{name}`")),
+                },
+                target => Err(format!("unknown target `{target}`")),
+            }
+        }
+        // INFO: pipes without a destination are broadcast - ignore the ones that aren't ours
+        _ => Ok(None),
+    }
+}
+
+fn command(payload: &str, clean: impl Fn(&str) -> String) -> Result<Request, String> {
+    let words = split(payload)?;
+    let (verb, name, rest) = match words.as_slice() {
+        [verb, name, rest @ ..] => (verb.as_str(), clean(name), rest),
+        [verb] => return Err(format!("`{verb}` is missing the segment name")),
+        [] => return Err("empty command".to_string()),
+    };
+    let envelope = |text: Option<String>| Envelope {
+        v: PROTOCOL_VERSION,
+        target: "segment".to_string(),
+        name: name.clone(),
+        text,
+        fg: None,
+        bg: None,
+        ttl: None,
+        value: None,
+        format: None,
+    };
+    match verb {
+        "set" if rest.is_empty() => Err("`set` is missing the text".to_string()),
+        "set" => Ok(Request::Segment(envelope(Some(clean(&rest.join(" ")))))),
+        "remove" => Ok(Request::Segment(envelope(None))),
+        "hide" => Ok(Request::Visibility { name, hidden: true }),
+        "show" => Ok(Request::Visibility {
+            name,
+            hidden: false,
+        }),
+        verb => Err(format!(
+            "unknown command `{verb}` (expected set, remove, hide or show)"
+        )),
+    }
+}
+
+// INFO: whitespace separated words, single or double quotes keep spaces - `set ci 'build #12'`
+fn split(payload: &str) -> Result<Vec<String>, String> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut quote = None;
+    for c in payload.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), c) => word.get_or_insert_default().push(c),
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                word.get_or_insert_default();
+            }
+            (None, c) if c.is_whitespace() => words.extend(word.take()),
+            (None, c) => word.get_or_insert_default().push(c),
+        }
+    }
+    if quote.is_some() {
+        return Err("unterminated quote".to_string());
+    }
+    words.extend(word);
+    Ok(words)
+}
+
+pub fn reply(message: &PipeMessage, output: &str) {
+    if let PipeSource::Cli(pipe_id) = &message.source {
+        compat::cli_pipe_output(pipe_id, &format!("{output}\n"));
+    }
+}
@@ -0,0 +1,45 @@
+use std::collections::BTreeMap;
+
+use anstyle::Style as AnsiStyle;
+
+use crate::{Palette, Segment};
+
+// NOTE: suffix of configuration keys like `http_placeholder = "loading"`, `hide` leaves the
+// element out until it has data
+pub const SUFFIX: &str = "_placeholder";
+
+const DEFAULT: &str = "…";
+const HIDE: &str = "hide";
+
+// NOTE: shown by polled elements before their first command or request came back
+#[derive(Default)]
+pub struct Placeholders {
+    texts: BTreeMap<String, String>,
+}
+
+impl Placeholders {
+    pub fn new(config: &BTreeMap<String, String>) -> Self {
+        let texts = config
+            .iter()
+            .filter_map(|(key, value)| Some((key.strip_suffix(SUFFIX)?.to_string(), value.clone())))
+            .collect();
+        Self { texts }
+    }
+
+    pub fn render(&self, name: &str, palette: &Palette) -> Segment {
+        let text = self.texts.get(name).map_or(DEFAULT, String::as_str);
+        if text == HIDE || text.is_empty() {
+            return Segment::empty();
+        }
+        // INFO: dimmed next to the elements that have data already
+        let palette = palette.dimmed();
+        Segment::new(
+            Box::new(text.to_string()),
+            AnsiStyle::new()
+                .fg_color(palette.black)
+                .bg_color(palette.gray)
+                .italic(),
+            &palette,
+        )
+    }
+}
@@ -0,0 +1,79 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Duration, Local};
+use zellij_tile::prelude::*;
+
+use crate::{compat, time};
+
+// NOTE: context key identifying which poll a `RunCommandResult` or `WebRequestResult` belongs to
+pub const POLL: &str = "poll";
+
+enum Source {
+    Command(String),
+    Url(String),
+}
+
+// NOTE: upper bound of the delay spreading first runs of polls sharing an interval
+const MAX_JITTER: f64 = 10.0;
+
+// NOTE: shell command or GET request re-run every `interval` seconds, driven by `Timer` events
+pub struct Poll {
+    name: &'static str,
+    source: Source,
+    interval: f64,
+    next: Option<DateTime<Local>>,
+}
+
+impl Poll {
+    pub fn new(name: &'static str, command: &str, interval: f64) -> Self {
+        Self {
+            name,
+            source: Source::Command(command.to_string()),
+            interval: interval.max(1.0),
+            next: None,
+        }
+    }
+
+    pub fn url(name: &'static str, url: &str, interval: f64) -> Self {
+        Self {
+            source: Source::Url(url.to_string()),
+            ..Self::new(name, "", interval)
+        }
+    }
+
+    // INFO: runs `factor` times less often - polls on battery power
+    pub fn slow_down(&mut self, factor: f64) {
+        self.interval *= factor.max(1.0);
+    }
+
+    // INFO: deterministic per name, so polls don't all start within the same tick
+    fn jitter(&self) -> f64 {
+        let hash = self.name.bytes().fold(2166136261u32, |hash, x| {
+            (hash ^ x as u32).wrapping_mul(16777619)
+        });
+        let bound = (self.interval.min(MAX_JITTER) * 1000.0) as u32;
+        (hash % bound.max(1)) as f64 / 1000.0
+    }
+
+    // INFO: runs the command when due and schedules the next wake up
+    pub fn tick(&mut self) {
+        let now = time::now();
+        let Some(next) = self.next else {
+            let jitter = self.jitter();
+            self.next = Some(now + Duration::milliseconds((jitter * 1000.0) as i64));
+            set_timeout(jitter);
+            return;
+        };
+        // INFO: timers may fire slightly early
+        if next > now + Duration::milliseconds(100) {
+            return;
+        }
+        let context = BTreeMap::from([(POLL.to_string(), self.name.to_string())]);
+        match &self.source {
+            Source::Command(command) => run_command(&["sh", "-c", command], context),
+            Source::Url(url) => compat::web_request(url, context),
+        }
+        self.next = Some(now + Duration::milliseconds((self.interval * 1000.0) as i64));
+        set_timeout(self.interval);
+    }
+}
@@ -0,0 +1,79 @@
+use anstyle::Style as AnsiStyle;
+use zellij_tile::prelude::*;
+
+use crate::{glyph, poll, widget::Widget, Palette, Segment};
+
+// NOTE: `online` of the AC adapter on linux, the first line of `pmset` on macOS
+pub const COMMAND: &str = "cat /sys/class/power_supply/*/online 2>/dev/null \
+     || pmset -g batt 2>/dev/null | head -1";
+
+// NOTE: whether the machine runs on AC or on battery power, next to the battery percentage.
+// Unknown on machines without a battery.
+#[derive(Default)]
+pub struct Power {
+    on_battery: Option<bool>,
+    loaded: bool,
+}
+
+impl Widget for Power {
+    fn interested_events(&self) -> Vec<EventType> {
+        vec![EventType::RunCommandResult]
+    }
+
+    // INFO: returns `true` when the power source changed
+    fn update(&mut self, event: &Event) -> bool {
+        let Event::RunCommandResult(_, stdout, _, context) = event else {
+            return false;
+        };
+        if context.get(poll::POLL).map(String::as_str) != Some("power") {
+            return false;
+        }
+        let on_battery = parse(&String::from_utf8_lossy(stdout));
+        let changed = self.on_battery != on_battery || !self.loaded;
+        self.on_battery = on_battery;
+        self.loaded = true;
+        changed
+    }
+
+    fn is_loaded(&self) -> bool {
+        self.loaded
+    }
+
+    fn render(&self, palette: &Palette) -> Segment {
+        let (icon, bg) = match self.on_battery {
+            Some(true) => (glyph("󰁹", "BAT"), palette.yellow),
+            Some(false) => (glyph("󰚥", "AC"), palette.gray),
+            None => return Segment::empty(),
+        };
+        Segment::new(
+            Box::new(icon),
+            AnsiStyle::new().fg_color(palette.black).bg_color(bg),
+            palette,
+        )
+    }
+}
+
+impl Power {
+    pub fn on_battery(&self) -> bool {
+        self.on_battery == Some(true)
+    }
+}
+
+// INFO: any adapter online means AC power
+fn parse(stdout: &str) -> Option<bool> {
+    if stdout.contains("AC Power") {
+        return Some(false);
+    }
+    if stdout.contains("Battery Power") {
+        return Some(true);
+    }
+    let online: Vec<&str> = stdout
+        .lines()
+        .map(str::trim)
+        .filter(|x| *x == "0" || *x == "1")
+        .collect();
+    if online.is_empty() {
+        return None;
+    }
+    Some(!online.contains(&"1"))
+}
@@ -0,0 +1,104 @@
+use std::{collections::BTreeMap, io::Read};
+
+use serde::Deserialize;
+use zellij_tile::prelude::*;
+
+use crate::{config, PluginState, Session};
+
+// NOTE: fake state of a headless render, read from stdin -
+// `{"widths":[80,160],"config":{"fixed_time":"2024-01-01T12:00:00Z"},"mode":"locked",
+//   "session":"docs","tabs":["editor","logs"],"active":1,
+//   "pipes":[{"name":"statusline","payload":"{\"target\":\"segment\",\"name\":\"cpu\",\"text\":\"42%\"}"}]}`
+#[derive(Deserialize)]
+struct State {
+    #[serde(default)]
+    config: BTreeMap<String, String>,
+    #[serde(default = "default_widths")]
+    widths: Vec<usize>,
+    #[serde(default = "default_mode")]
+    mode: InputMode,
+    session: Option<String>,
+    #[serde(default)]
+    tabs: Vec<String>,
+    #[serde(default)]
+    active: usize,
+    // INFO: messages handled as if they were piped, e.g. segments and `statusline::context`
+    #[serde(default)]
+    pipes: Vec<Pipe>,
+}
+
+#[derive(Deserialize)]
+struct Pipe {
+    name: String,
+    payload: Option<String>,
+    #[serde(default)]
+    args: BTreeMap<String, String>,
+}
+
+fn default_widths() -> Vec<usize> {
+    vec![80, 120, 200]
+}
+
+fn default_mode() -> InputMode {
+    InputMode::Normal
+}
+
+// NOTE: renders the bar for every width of the state, one line each, without running zellij -
+// `wasmtime run --invoke preview zellij-statusline.wasm < state.json`. Zellij imports are never
+// called on this path, the runtime only has to tolerate them being unresolved.
+#[no_mangle]
+pub fn preview() {
+    let mut input = String::new();
+    if let Err(err) = std::io::stdin().read_to_string(&mut input) {
+        eprintln!("error: failed to read the state: {err}");
+        return;
+    }
+    let state: State = match serde_json::from_str(&input) {
+        Ok(state) => state,
+        Err(err) => {
+            eprintln!("error: invalid state: {err}");
+            return;
+        }
+    };
+
+    let mut plugin = PluginState {
+        headless: true,
+        ..Default::default()
+    };
+    plugin.widgets = plugin.registry();
+    plugin.config = config::effective(&state.config);
+    plugin.build();
+
+    plugin.update(Event::ModeUpdate(ModeInfo {
+        mode: state.mode,
+        ..Default::default()
+    }));
+    if let Some(session) = state.session {
+        *plugin.session.borrow_mut() = Session(session);
+    }
+    let tabs = state
+        .tabs
+        .into_iter()
+        .enumerate()
+        .map(|(position, name)| TabInfo {
+            position,
+            name,
+            active: position == state.active,
+            ..Default::default()
+        })
+        .collect();
+    plugin.update(Event::TabUpdate(tabs));
+    for pipe in state.pipes {
+        plugin.pipe(PipeMessage {
+            source: PipeSource::Keybind,
+            name: pipe.name,
+            payload: pipe.payload,
+            args: pipe.args,
+            is_private: false,
+        });
+    }
+
+    for width in state.widths {
+        println!("{}\x1b[0m", plugin.render_to_string(width));
+    }
+}
@@ -0,0 +1,100 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Local, NaiveTime};
+use chrono_tz::Tz;
+
+use crate::config;
+
+// NOTE: prefix of configuration keys like `profile_0900 = "work"` and `profile_1800 = "minimal"`
+pub const PREFIX: &str = "profile_";
+
+// NOTE: prefix of layouts named by profiles - `layout_work = "mode session tabs | git clock"`
+pub const LAYOUT_PREFIX: &str = "layout_";
+
+// NOTE: profiles switched on a daily schedule, each one selects the preset and/or the
+// `layout_<name>` of its name until the next one takes over
+#[derive(Default)]
+pub struct Profiles {
+    schedule: Vec<(NaiveTime, String)>,
+    // NOTE: the schedule follows the clock rather than the UTC of the plugin sandbox
+    timezone: Option<Tz>,
+}
+
+impl Profiles {
+    // INFO: keys with an invalid time are left out, `config::parse` reports them
+    pub fn new(config: &BTreeMap<String, String>) -> Self {
+        let mut schedule: Vec<_> = config
+            .iter()
+            .filter_map(|(key, value)| Some((parse(key.strip_prefix(PREFIX)?)?, value.clone())))
+            .collect();
+        schedule.sort();
+        let timezone = config.get("timezone").and_then(|x| x.parse().ok());
+        Self { schedule, timezone }
+    }
+
+    // INFO: the last one scheduled before `now`, yesterday's last one early in the morning
+    pub fn active(&self, now: DateTime<Local>) -> Option<&str> {
+        let now = self.time_of_day(now);
+        self.schedule
+            .iter()
+            .rev()
+            .find(|(at, _)| *at <= now)
+            .or(self.schedule.last())
+            .map(|(_, name)| name.as_str())
+    }
+
+    // INFO: seconds until the next profile takes over
+    pub fn next_switch(&self, now: DateTime<Local>) -> Option<f64> {
+        let now = self.time_of_day(now);
+        let (at, _) = self
+            .schedule
+            .iter()
+            .find(|(at, _)| *at > now)
+            .or(self.schedule.first())?;
+        let seconds = (*at - now).num_milliseconds() as f64 / 1000.0;
+        Some(if seconds > 0.0 {
+            seconds
+        } else {
+            seconds + 86400.0
+        })
+    }
+
+    fn time_of_day(&self, now: DateTime<Local>) -> NaiveTime {
+        now.with_timezone(&self.timezone.unwrap_or(chrono_tz::UTC))
+            .time()
+    }
+}
+
+// INFO: profiles only fill in `preset` and `layout` on top of the rest of the configuration
+pub fn apply(name: &str, config: &mut BTreeMap<String, String>) {
+    if config::presets().any(|x| x == name) {
+        config.insert("preset".to_string(), name.to_string());
+    }
+    if let Some(layout) = config.get(&format!("{LAYOUT_PREFIX}{name}")).cloned() {
+        config.insert("layout".to_string(), layout);
+    }
+}
+
+pub fn validate(key: &str, name: &str, config: &BTreeMap<String, String>) -> Result<(), String> {
+    let at = key.strip_prefix(PREFIX).unwrap_or(key);
+    if parse(at).is_none() {
+        return Err(format!("invalid time `{at}` (expected `HHMM`)"));
+    }
+    validate_name(name, config)
+}
+
+pub fn validate_name(name: &str, config: &BTreeMap<String, String>) -> Result<(), String> {
+    if !config::presets().any(|x| x == name)
+        && !config.contains_key(&format!("{LAYOUT_PREFIX}{name}"))
+    {
+        return Err(format!(
+            "unknown profile `{name}` (neither a preset nor `{LAYOUT_PREFIX}{name}`)"
+        ));
+    }
+    Ok(())
+}
+
+// INFO: `HHMM` - `0930`
+fn parse(at: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(at, "%H%M").ok()
+}
@@ -0,0 +1,138 @@
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{BufRead, Write},
+};
+
+use chrono::DateTime;
+use serde::{Deserialize, Serialize};
+use zellij_tile::prelude::*;
+
+use crate::{time, PluginState};
+
+// NOTE: received events as JSON lines, attached to bug reports and fed to `replay`
+pub const RECORDING_PATH: &str = "/data/events.jsonl";
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Entry {
+    // INFO: effective configuration, the first line of every recording
+    Config(BTreeMap<String, String>),
+    Event {
+        at: String,
+        event: Event,
+    },
+    Pipe {
+        at: String,
+        name: String,
+        payload: Option<String>,
+        args: BTreeMap<String, String>,
+    },
+    Render {
+        at: String,
+        rows: usize,
+        cols: usize,
+    },
+}
+
+// NOTE: opt-in, toggled through `statusline::record`
+#[derive(Default)]
+pub struct Recorder {
+    file: Option<File>,
+}
+
+impl Recorder {
+    // INFO: returns `true` when recording started, a new recording replaces the previous one
+    pub fn toggle(&mut self, config: &BTreeMap<String, String>) -> Result<bool, String> {
+        if self.file.take().is_some() {
+            return Ok(false);
+        }
+        let file = File::create(RECORDING_PATH).map_err(|err| err.to_string())?;
+        self.file = Some(file);
+        self.record(Entry::Config(config.clone()));
+        Ok(true)
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.file.is_some()
+    }
+
+    // INFO: stops recording on the first failed write
+    pub fn record(&mut self, entry: Entry) {
+        let Some(file) = &mut self.file else {
+            return;
+        };
+        let Ok(line) = serde_json::to_string(&entry) else {
+            return;
+        };
+        if writeln!(file, "{line}").is_err() {
+            self.file = None;
+        }
+    }
+}
+
+pub fn now() -> String {
+    time::now().to_rfc3339()
+}
+
+// NOTE: feeds a recording through `update` and `pipe` with the clock pinned to the recorded
+// instants, printing the bar at every recorded render -
+// `wasmtime run --invoke replay zellij-statusline.wasm < events.jsonl`
+#[no_mangle]
+pub fn replay() {
+    let mut plugin = PluginState {
+        headless: true,
+        ..Default::default()
+    };
+    plugin.widgets = plugin.registry();
+
+    let pin = |at: &str| {
+        if let Ok(at) = DateTime::parse_from_rfc3339(at) {
+            time::set_fixed(Some(at.into()));
+        }
+    };
+    for (number, line) in std::io::stdin().lock().lines().enumerate() {
+        let Ok(line) = line else {
+            break;
+        };
+        let entry = match serde_json::from_str::<Entry>(&line) {
+            Ok(entry) => entry,
+            Err(err) => {
+                eprintln!("error: line {}: {err}", number + 1);
+                continue;
+            }
+        };
+        match entry {
+            // INFO: commands and requests aren't run again, their recorded results are replayed
+            Entry::Config(config) => {
+                plugin.config = config;
+                plugin.build();
+                plugin.polls.clear();
+            }
+            Entry::Event { at, event } => {
+                pin(&at);
+                plugin.update(event);
+            }
+            Entry::Pipe {
+                at,
+                name,
+                payload,
+                args,
+            } => {
+                pin(&at);
+                plugin.pipe(PipeMessage {
+                    source: PipeSource::Keybind,
+                    name,
+                    payload,
+                    args,
+                    is_private: false,
+                });
+            }
+            Entry::Render { at, rows, cols } => {
+                pin(&at);
+                plugin.render_frame(rows, cols);
+                println!("{}", plugin.frame);
+            }
+        }
+    }
+}
@@ -0,0 +1,6 @@
+// NOTE: elements laying themselves out within a number of terminal cells - each one shortens
+// what it shows on its own instead of the caller measuring stripped strings. Returns the
+// rendered string along with the cells it takes.
+pub trait RenderWidth {
+    fn render(&self, max_cells: usize) -> (String, usize);
+}
@@ -0,0 +1,102 @@
+use std::collections::BTreeMap;
+
+use anstyle::Style as AnsiStyle;
+
+use crate::parse_color;
+
+// NOTE: prefix of configuration keys like `rule_cpu = ">90:red,blink; >70:yellow"`
+const RULE_PREFIX: &str = "rule_";
+
+#[derive(Clone, Copy)]
+enum Comparison {
+    Greater,
+    GreaterOrEqual,
+    Less,
+    LessOrEqual,
+    Equal,
+}
+
+struct Rule {
+    comparison: Comparison,
+    threshold: f64,
+    style: Vec<String>,
+}
+
+impl Rule {
+    fn parse(rule: &str) -> Option<Self> {
+        let (condition, style) = rule.split_once(':')?;
+        let condition = condition.trim();
+        let (comparison, threshold) = [
+            (">=", Comparison::GreaterOrEqual),
+            ("<=", Comparison::LessOrEqual),
+            (">", Comparison::Greater),
+            ("<", Comparison::Less),
+            ("=", Comparison::Equal),
+        ]
+        .into_iter()
+        .find_map(|(prefix, comparison)| Some((comparison, condition.strip_prefix(prefix)?)))?;
+
+        Some(Self {
+            comparison,
+            threshold: threshold.trim().parse().ok()?,
+            style: style.split(',').map(|x| x.trim().to_string()).collect(),
+        })
+    }
+
+    fn matches(&self, value: f64) -> bool {
+        match self.comparison {
+            Comparison::Greater => value > self.threshold,
+            Comparison::GreaterOrEqual => value >= self.threshold,
+            Comparison::Less => value < self.threshold,
+            Comparison::LessOrEqual => value <= self.threshold,
+            Comparison::Equal => value == self.threshold,
+        }
+    }
+
+    // INFO: plain colors set the background, `fg=<color>` the foreground, the rest are effects
+    fn apply(&self, mut style: AnsiStyle) -> AnsiStyle {
+        for token in &self.style {
+            style = match token.as_str() {
+                "bold" => style.bold(),
+                "dim" => style.dimmed(),
+                "italic" => style.italic(),
+                "underline" => style.underline(),
+                "blink" => style.blink(),
+                "reverse" => style.invert(),
+                token => match token.strip_prefix("fg=") {
+                    Some(color) => style.fg_color(parse_color(color).or(style.get_fg_color())),
+                    None => style.bg_color(parse_color(token).or(style.get_bg_color())),
+                },
+            };
+        }
+        style
+    }
+}
+
+// NOTE: first matching rule of a segment wins
+#[derive(Default)]
+pub struct Rules {
+    rules: BTreeMap<String, Vec<Rule>>,
+}
+
+impl Rules {
+    pub fn new(config: &BTreeMap<String, String>) -> Self {
+        let rules = config
+            .iter()
+            .filter_map(|(key, value)| {
+                let name = key.strip_prefix(RULE_PREFIX)?;
+                let rules = value.split(';').filter_map(Rule::parse).collect();
+                Some((name.to_string(), rules))
+            })
+            .collect();
+
+        Self { rules }
+    }
+
+    pub fn style(&self, name: &str, value: f64, style: AnsiStyle) -> AnsiStyle {
+        self.rules
+            .get(name)
+            .and_then(|rules| rules.iter().find(|x| x.matches(value)))
+            .map_or(style, |rule| rule.apply(style))
+    }
+}
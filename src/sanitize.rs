@@ -0,0 +1,72 @@
+use crate::{budget::escape_len, truncate, width};
+use unicode_normalization::UnicodeNormalization;
+
+// NOTE: text from pipes, commands and pane titles ends up in the middle of the bar - escape
+// sequences in it could move the cursor, set the terminal title or restyle everything after it.
+// Whole sequences are dropped, line breaks and tabs become spaces, other control characters
+// (C0, DEL and C1) are dropped. The result is composed (NFC), so combining sequences count as the
+// cells they take.
+pub fn text(text: &str) -> String {
+    let mut sanitized = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        if c == '\x1b' {
+            rest = &rest[escape_len(rest).min(rest.len())..];
+            continue;
+        }
+        match c {
+            '\n' | '\r' | '\t' => sanitized.push(' '),
+            c if c.is_control() => {}
+            c => sanitized.push(c),
+        }
+        rest = &rest[c.len_utf8()..];
+    }
+    sanitized.nfc().collect()
+}
+
+// NOTE: piped payloads are additionally cut at `max_width` cells, never inside a grapheme
+// cluster (combining accents, ZWJ sequences, flags)
+pub fn payload(text: &str, max_width: usize) -> String {
+    let sanitized = self::text(text);
+    if width(&sanitized) <= max_width {
+        return sanitized;
+    }
+    let (mut truncated, _) = truncate(&sanitized, max_width.saturating_sub(1));
+    truncated.push('…');
+    truncated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn composes_combining_accents() {
+        assert_eq!(text("cafe\u{301}"), "caf\u{e9}");
+        assert_eq!(width(&text("cafe\u{301}")), 4);
+    }
+
+    #[test]
+    fn keeps_clusters_whole_when_truncating() {
+        let family = "\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467}";
+        let flag = "\u{1f1f5}\u{1f1f1}";
+        assert_eq!(
+            payload(&format!("{family}{flag}ab"), 5),
+            format!("{family}{flag}…")
+        );
+        assert_eq!(
+            payload(&format!("{flag}{flag}{flag}"), 3),
+            format!("{flag}…")
+        );
+    }
+
+    #[test]
+    fn keeps_short_payloads() {
+        assert_eq!(payload("e\u{301}", 1), "\u{e9}");
+    }
+
+    #[test]
+    fn drops_escape_sequences() {
+        assert_eq!(text("a\x1b[31mb\x1b]0;title\x07c\nd"), "abc d");
+    }
+}
@@ -0,0 +1,141 @@
+use std::collections::BTreeMap;
+
+use anstyle::Style as AnsiStyle;
+#[cfg(feature = "scripting")]
+use chrono::{Datelike, Timelike};
+#[cfg(feature = "scripting")]
+use rhai::{Dynamic, Engine, Map, Scope, AST};
+
+#[cfg(feature = "scripting")]
+use crate::parse_color;
+use crate::{memo, time, widget::Widget, Palette, Segment};
+
+// NOTE: scripts run on every render, a runaway loop is cut short instead of freezing the bar
+#[cfg(feature = "scripting")]
+const MAX_OPERATIONS: u64 = 50_000;
+
+// NOTE: variables of `text` templates passed as numbers instead of strings
+#[cfg(feature = "scripting")]
+const NUMBERS: &[&str] = &["tab_count", "cols"];
+
+// NOTE: rhai snippet computing a segment - `if tab_count > 9 { #{ text: "tabs!", bg: "red" } }`.
+// Sees the `text` template variables along with `hour`, `minute` and `day` and returns either
+// a string or a map of `text`, `fg`, `bg` and `bold`.
+#[derive(Default)]
+pub struct Script {
+    #[cfg(feature = "scripting")]
+    compiled: Option<(Engine, AST)>,
+    pub vars: BTreeMap<&'static str, String>,
+}
+
+impl Widget for Script {
+    fn render(&self, palette: &Palette) -> Segment {
+        match self.evaluate(palette) {
+            Ok(Some((text, style))) => Segment::new(Box::new(text), style, palette),
+            Ok(None) => Segment::empty(),
+            Err(err) => Segment::new(
+                Box::new(format!(" {err}")),
+                AnsiStyle::new()
+                    .fg_color(palette.white)
+                    .bg_color(palette.red),
+                palette,
+            )
+            .max_width(48),
+        }
+    }
+
+    // INFO: `hour`, `minute` and `day` change once a minute
+    fn state(&self) -> Option<u64> {
+        Some(memo::hash(&(&self.vars, time::now().timestamp() / 60)))
+    }
+}
+
+impl Script {
+    // INFO: invalid scripts are left out, `config::parse` reports them
+    pub fn new(source: Option<&str>) -> Self {
+        #[cfg(feature = "scripting")]
+        let compiled = source.and_then(|source| {
+            let engine = engine();
+            let ast = engine.compile(source).ok()?;
+            Some((engine, ast))
+        });
+        #[cfg(not(feature = "scripting"))]
+        let _ = source;
+        Self {
+            #[cfg(feature = "scripting")]
+            compiled,
+            vars: BTreeMap::new(),
+        }
+    }
+
+    #[cfg(feature = "scripting")]
+    fn evaluate(&self, palette: &Palette) -> Result<Option<(String, AnsiStyle)>, String> {
+        let Some((engine, ast)) = &self.compiled else {
+            return Ok(None);
+        };
+        let mut scope = Scope::new();
+        for (name, value) in &self.vars {
+            match value.parse::<i64>() {
+                Ok(number) if NUMBERS.contains(name) => scope.push(*name, number),
+                _ => scope.push(*name, value.clone()),
+            };
+        }
+        let now = time::now();
+        scope.push("hour", now.hour() as i64);
+        scope.push("minute", now.minute() as i64);
+        scope.push("day", now.weekday().to_string().to_lowercase());
+
+        let result = engine
+            .eval_ast_with_scope::<Dynamic>(&mut scope, ast)
+            .map_err(|err| err.to_string())?;
+        let style = AnsiStyle::new()
+            .fg_color(palette.black)
+            .bg_color(palette.gray);
+        if result.is_unit() {
+            return Ok(None);
+        }
+        let Some(map) = result.clone().try_cast::<Map>() else {
+            let text = result.to_string();
+            return Ok(Some((text, style)).filter(|(x, _)| !x.is_empty()));
+        };
+
+        let text = map.get("text").map(ToString::to_string).unwrap_or_default();
+        let color = |key: &str| map.get(key).and_then(|x| parse_color(&x.to_string()));
+        let mut style = style;
+        if let Some(fg) = color("fg") {
+            style = style.fg_color(Some(fg));
+        }
+        if let Some(bg) = color("bg") {
+            style = style.bg_color(Some(bg));
+        }
+        if map.get("bold").and_then(|x| x.as_bool().ok()) == Some(true) {
+            style = style.bold();
+        }
+        Ok(Some((text, style)).filter(|(x, _)| !x.is_empty()))
+    }
+
+    #[cfg(not(feature = "scripting"))]
+    fn evaluate(&self, _palette: &Palette) -> Result<Option<(String, AnsiStyle)>, String> {
+        Ok(None)
+    }
+}
+
+#[cfg(feature = "scripting")]
+fn engine() -> Engine {
+    let mut engine = Engine::new();
+    engine.set_max_operations(MAX_OPERATIONS);
+    engine
+}
+
+pub fn validate(source: &str) -> Result<(), String> {
+    #[cfg(feature = "scripting")]
+    return engine()
+        .compile(source)
+        .map(|_| ())
+        .map_err(|err| err.to_string());
+    #[cfg(not(feature = "scripting"))]
+    {
+        let _ = source;
+        Err("built without the `scripting` feature".to_string())
+    }
+}
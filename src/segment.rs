@@ -1,90 +1,380 @@
-use std::{fmt::Display, ops::Range};
+use std::{
+    cell::{Cell, RefCell},
+    fmt::Display,
+    ops::Range,
+    rc::Rc,
+    str::FromStr,
+};
 
-use anstyle::{AnsiColor, Color, RgbColor, Style as AnsiStyle};
-use lazy_static::lazy_static;
-use unicode_segmentation::UnicodeSegmentation;
+use anstyle::{Ansi256Color, AnsiColor, Color, Effects, Reset, RgbColor, Style as AnsiStyle};
 use zellij_tile::prelude::*;
 
-lazy_static! {
-    pub static ref BG: Option<Color> = Some(AnsiColor::Black.into());
-    pub static ref RED: Option<Color> = Some(AnsiColor::Red.into());
-    pub static ref GREEN: Option<Color> = Some(AnsiColor::Green.into());
-    pub static ref YELLOW: Option<Color> = Some(AnsiColor::Yellow.into());
-    pub static ref BLUE: Option<Color> = Some(AnsiColor::Blue.into());
-    pub static ref MAGENTA: Option<Color> = Some(AnsiColor::Magenta.into());
-    pub static ref CYAN: Option<Color> = Some(AnsiColor::Cyan.into());
-    pub static ref GRAY: Option<Color> = Some(AnsiColor::White.into());
-    pub static ref WHITE: Option<Color> = Some(AnsiColor::BrightWhite.into());
-    pub static ref BLACK: Option<Color> = Some(RgbColor(0u8, 0u8, 0u8).into());
+use crate::{palette::Palette, truncate, truncate_start, DisplayExt, RenderWidth};
+
+// NOTE: accepts ANSI color names, 256-color palette indexes and `#rrggbb`
+pub fn parse_color(value: &str) -> Option<Color> {
+    let value = value.trim().to_lowercase();
+    if let Some(hex) = value.strip_prefix('#') {
+        let rgb = u32::from_str_radix(hex, 16)
+            .ok()
+            .filter(|_| hex.len() == 6)?;
+        return Some(RgbColor((rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8).into());
+    }
+    if let Ok(index) = value.parse::<u8>() {
+        return Some(Ansi256Color(index).into());
+    }
+
+    let color = match value.as_str() {
+        "black" => AnsiColor::Black,
+        "red" => AnsiColor::Red,
+        "green" => AnsiColor::Green,
+        "yellow" => AnsiColor::Yellow,
+        "blue" => AnsiColor::Blue,
+        "magenta" => AnsiColor::Magenta,
+        "cyan" => AnsiColor::Cyan,
+        "white" => AnsiColor::White,
+        "bright_black" | "gray" | "grey" => AnsiColor::BrightBlack,
+        "bright_red" => AnsiColor::BrightRed,
+        "bright_green" => AnsiColor::BrightGreen,
+        "bright_yellow" => AnsiColor::BrightYellow,
+        "bright_blue" => AnsiColor::BrightBlue,
+        "bright_magenta" => AnsiColor::BrightMagenta,
+        "bright_cyan" => AnsiColor::BrightCyan,
+        "bright_white" => AnsiColor::BrightWhite,
+        _ => return None,
+    };
+    Some(color.into())
+}
+
+thread_local! {
+    // NOTE: plain fonts show boxes in place of powerline and nerd font glyphs, `nerd_fonts =
+    // false` swaps them for ASCII
+    static NERD_FONTS: Cell<bool> = const { Cell::new(true) };
+}
+
+pub fn set_nerd_fonts(enabled: bool) {
+    NERD_FONTS.with(|x| x.set(enabled));
+}
+
+pub fn nerd_fonts() -> bool {
+    NERD_FONTS.with(Cell::get)
+}
+
+thread_local! {
+    // NOTE: blinking segments are held still in low power mode
+    static ANIMATIONS: Cell<bool> = const { Cell::new(true) };
+}
+
+pub fn set_animations(enabled: bool) {
+    ANIMATIONS.with(|x| x.set(enabled));
+}
+
+pub fn animations() -> bool {
+    ANIMATIONS.with(Cell::get)
+}
+
+thread_local! {
+    // NOTE: colored and curly underlines (SGR 4:3 and 58) and strikethrough, left out unless the
+    // terminal is known to draw them
+    static EXTENDED_SGR: Cell<bool> = const { Cell::new(false) };
+}
+
+pub fn set_extended_sgr(enabled: bool) {
+    EXTENDED_SGR.with(|x| x.set(enabled));
+}
+
+// INFO: `nerd` unless nerd fonts are disabled
+pub fn glyph(nerd: &'static str, ascii: &'static str) -> &'static str {
+    if nerd_fonts() {
+        nerd
+    } else {
+        ascii
+    }
+}
+
+thread_local! {
+    // NOTE: segment caps selected by the `separator` configuration key
+    static SEPARATOR: Cell<(&'static str, &'static str)> = const { Cell::new(("", "")) };
+}
+
+#[derive(Clone, Copy)]
+enum TabIndex {
+    Decimal,
+    Superscript,
+    Hex,
+    Alpha,
+}
+
+thread_local! {
+    // NOTE: style of tab numbers selected by the `tab_index` configuration key
+    static TAB_INDEX: Cell<TabIndex> = const { Cell::new(TabIndex::Decimal) };
+}
+
+pub fn set_tab_index(name: &str) {
+    let style = match name {
+        "superscript" => TabIndex::Superscript,
+        "hex" => TabIndex::Hex,
+        "alpha" => TabIndex::Alpha,
+        _ => TabIndex::Decimal,
+    };
+    TAB_INDEX.with(|x| x.set(style));
+}
+
+// INFO: 1-based number of the tab at `position`. `hex` and `alpha` keep it a single cell - `1`
+// to `f` and `1` to `9` then `a` to `z` - and tabs past the last one all show `+`
+pub fn tab_index(position: usize) -> String {
+    const BEYOND: &str = "+";
+    let number = position + 1;
+    match TAB_INDEX.with(Cell::get) {
+        TabIndex::Decimal => number.to_string(),
+        TabIndex::Superscript => number
+            .to_string()
+            .chars()
+            .map(|x| ['⁰', '¹', '²', '³', '⁴', '⁵', '⁶', '⁷', '⁸', '⁹'][x as usize - '0' as usize])
+            .collect(),
+        TabIndex::Hex => match number {
+            1..=15 => format!("{number:x}"),
+            _ => BEYOND.to_string(),
+        },
+        TabIndex::Alpha => match number {
+            1..=9 => number.to_string(),
+            10..=35 => char::from(b'a' + (number - 10) as u8).to_string(),
+            _ => BEYOND.to_string(),
+        },
+    }
+}
+
+thread_local! {
+    // NOTE: glyphs pointing the other way for segments of the right group, selected by the
+    // `separator_right` configuration key - `None` keeps the ones of `separator`
+    static SEPARATOR_RIGHT: Cell<Option<(&'static str, &'static str)>> = const { Cell::new(None) };
+}
+
+// INFO: segments are only told apart by their colors without nerd fonts
+pub fn set_separator(name: &str) {
+    let name = if nerd_fonts() { name } else { "none" };
+    let caps = match name {
+        "round" => ("", ""),
+        "flame" => ("", ""),
+        "none" => ("", ""),
+        _ => ("", ""),
+    };
+    SEPARATOR.with(|x| x.set(caps));
+}
+
+// INFO: without glyphs there's nothing to mirror
+pub fn set_separator_right(name: Option<&str>) {
+    let caps = name.filter(|_| nerd_fonts()).and_then(|x| match x {
+        "round" => Some(("", "")),
+        "angled" => Some(("", "")),
+        "flame" => Some(("", "")),
+        _ => None,
+    });
+    SEPARATOR_RIGHT.with(|x| x.set(caps));
+}
+
+pub fn separator() -> (&'static str, &'static str) {
+    SEPARATOR.with(Cell::get)
+}
+
+pub fn separator_right() -> Option<(&'static str, &'static str)> {
+    SEPARATOR_RIGHT.with(Cell::get)
+}
+
+// NOTE: segments of a lower priority are shrunk and dropped first on narrow terminals
+pub const DEFAULT_PRIORITY: u8 = 50;
+
+thread_local! {
+    // NOTE: marks content cut at `max_width`, selected by the `ellipsis` configuration key
+    static ELLIPSIS: RefCell<String> = RefCell::new("...".to_string());
+}
+
+pub fn set_ellipsis(ellipsis: &str) {
+    ELLIPSIS.with_borrow_mut(|x| ellipsis.clone_into(x));
 }
 
+// NOTE: content narrower than the minimum width of a segment is placed at its `align`
+#[derive(Clone, Copy, Default, PartialEq)]
+pub enum Align {
+    Left,
+    #[default]
+    Center,
+    Right,
+}
+
+impl FromStr for Align {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "left" => Ok(Self::Left),
+            "center" => Ok(Self::Center),
+            "right" => Ok(Self::Right),
+            _ => Err("expected left, center or right".to_string()),
+        }
+    }
+}
+
+thread_local! {
+    // NOTE: cells around the content of every segment and its alignment, selected by the
+    // `padding_left`, `padding_right` and `align` configuration keys
+    static PADDING: Cell<(usize, usize)> = const { Cell::new((1, 1)) };
+    static ALIGN: Cell<Align> = const { Cell::new(Align::Center) };
+}
+
+pub fn set_padding(left: usize, right: usize) {
+    PADDING.with(|x| x.set((left, right)));
+}
+
+pub fn set_align(align: Align) {
+    ALIGN.with(|x| x.set(align));
+}
+
+// NOTE: side content too wide for a segment is cut from - `left` keeps the tail of long paths
+#[derive(Clone, Copy, Default, PartialEq)]
+pub enum Truncate {
+    Left,
+    Middle,
+    #[default]
+    Right,
+}
+
+impl FromStr for Truncate {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "left" => Ok(Self::Left),
+            "middle" => Ok(Self::Middle),
+            "right" => Ok(Self::Right),
+            _ => Err("expected left, middle or right".to_string()),
+        }
+    }
+}
+
+#[derive(Clone)]
 pub struct Segment {
-    content: Box<dyn Display>,
+    // NOTE: shared so memoized segments are cloned without rendering their content again
+    content: Rc<dyn Display>,
+    // NOTE: shorter content swapped in by `shrink`
+    compact: Option<Rc<dyn Display>>,
     style: AnsiStyle,
+    priority: u8,
 
     min_content_width: usize,
     max_content_width: usize,
-    padding_left: &'static str,
-    padding_right: &'static str,
+    truncate: Truncate,
+    align: Align,
+    padding_left: usize,
+    padding_right: usize,
     begin: &'static str,
     end: &'static str,
+    mirrored: bool,
+    // NOTE: set by `join` - background of the following segment and whether the previous one
+    // already drew the transition
+    next: Option<Option<Color>>,
+    joined: bool,
+    raw: bool,
+    // NOTE: colors of the separators against the bar and of `highlighted`, `flashed` and `faded`
+    palette: Palette,
 }
 
 impl Segment {
-    pub fn new(content: Box<dyn Display>, style: AnsiStyle) -> Self {
+    pub fn new(content: Box<dyn Display>, style: AnsiStyle, palette: &Palette) -> Self {
         Segment {
-            content,
+            content: content.into(),
             style,
 
-            ..Default::default()
+            ..Self::base(palette)
+        }
+    }
+
+    // INFO: already rendered segments of widgets showing several of them - written as they are
+    pub fn raw(content: Box<dyn Display>) -> Self {
+        Segment {
+            content: content.into(),
+            raw: true,
+
+            ..Self::base(&Palette::default())
         }
     }
 
-    pub fn new_tab(tab: &TabInfo) -> Self {
-        let color = if tab.active { *YELLOW } else { *GRAY };
-        let fullscreen = if tab.is_fullscreen_active { "󰊓" } else { "" };
-        let sync = if tab.is_sync_panes_active { "󱍸" } else { "" };
+    // INFO: widgets without anything to show
+    pub fn empty() -> Self {
+        Self::raw(Box::new(""))
+    }
+
+    pub fn new_tab(tab: &TabInfo, palette: &Palette) -> Self {
+        let color = if tab.active {
+            palette.yellow
+        } else {
+            palette.gray
+        };
+        let fullscreen = if tab.is_fullscreen_active {
+            glyph("󰊓", "[F]")
+        } else {
+            ""
+        };
+        let sync = if tab.is_sync_panes_active {
+            glyph("󱍸", "[S]")
+        } else {
+            ""
+        };
         let content = format!(
-            "{}  {} {}{}",
-            tab.position + 1,
+            "{} {} {} {}{}",
+            tab_index(tab.position),
+            glyph("", "|"),
             tab.name.clone(),
             sync,
             fullscreen
         );
-        Segment {
-            content: Box::new(content),
-            style: AnsiStyle::new().fg_color(*BLACK).bg_color(color),
+        let segment = Segment {
+            content: Rc::new(content),
+            style: AnsiStyle::new().fg_color(palette.black).bg_color(color),
 
-            ..Default::default()
+            ..Self::base(palette)
+        };
+        if tab.active {
+            segment.accent(palette.magenta)
+        } else {
+            segment
         }
     }
 
-    pub fn new_compact_tab(tab: &TabInfo) -> Self {
+    pub fn new_compact_tab(tab: &TabInfo, palette: &Palette) -> Self {
         if tab.active {
-            return Self::new_tab(tab);
+            return Self::new_tab(tab, palette);
         }
 
-        let color = if tab.active { *YELLOW } else { *GRAY };
-        let content = format!("{}", tab.position + 1);
+        let content = tab_index(tab.position);
         Segment {
-            content: Box::new(content),
-            style: AnsiStyle::new().fg_color(*BLACK).bg_color(color),
+            content: Rc::new(content),
+            style: AnsiStyle::new()
+                .fg_color(palette.black)
+                .bg_color(palette.gray),
 
-            ..Default::default()
+            ..Self::base(palette)
         }
     }
 
-    pub fn new_range_tab(range: Range<usize>) -> Self {
+    pub fn new_range_tab(range: Range<usize>, palette: &Palette) -> Self {
         let content = if range.is_empty() {
-            format!("{}", range.start + 1)
+            tab_index(range.start)
         } else {
-            format!("{}  󰜴  {}", range.start + 1, range.end + 1)
+            let (separator, arrow) = (glyph("", "|"), glyph("󰜴", ".."));
+            format!(
+                "{} {separator} {arrow} {separator} {}",
+                tab_index(range.start),
+                tab_index(range.end)
+            )
         };
         Segment {
-            content: Box::new(content),
-            style: AnsiStyle::new().fg_color(*BLACK).bg_color(*GRAY),
+            content: Rc::new(content),
+            style: AnsiStyle::new()
+                .fg_color(palette.black)
+                .bg_color(palette.gray),
 
-            ..Default::default()
+            ..Self::base(palette)
         }
     }
 
@@ -97,58 +387,430 @@ impl Segment {
         self.max_content_width = width;
         self
     }
+
+    pub fn truncate(mut self, truncate: Truncate) -> Self {
+        self.truncate = truncate;
+        self
+    }
+
+    pub fn align(mut self, align: Align) -> Self {
+        self.align = align;
+        self
+    }
+
+    // INFO: right group segments pointing the other way when `separator_right` is set
+    pub fn mirrored(mut self) -> Self {
+        if let Some((begin, end)) = separator_right() {
+            (self.begin, self.end) = (begin, end);
+            self.mirrored = true;
+        }
+        self
+    }
+
+    pub fn padding(mut self, left: usize, right: usize) -> Self {
+        self.padding_left = left;
+        self.padding_right = right;
+        self
+    }
+
+    // INFO: colors overriding the ones of the widget, `None` keeps them
+    pub fn themed(mut self, fg: Option<Color>, bg: Option<Color>) -> Self {
+        if let Some(fg) = fg {
+            self.style = self.style.fg_color(Some(fg));
+        }
+        if let Some(bg) = bg {
+            self.style = self.style.bg_color(Some(bg));
+        }
+        self
+    }
+
+    pub fn priority(mut self, priority: u8) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    pub fn compact(mut self, content: Box<dyn Display>) -> Self {
+        self.compact = Some(content.into());
+        self
+    }
+
+    pub fn get_priority(&self) -> u8 {
+        self.priority
+    }
+
+    // INFO: `(fg, bg)` of the segment, `None` for raw ones carrying their own colors
+    pub fn colors(&self) -> Option<(Option<Color>, Option<Color>)> {
+        (!self.raw).then(|| (self.style.get_fg_color(), self.style.get_bg_color()))
+    }
+
+    // INFO: returns `false` when there's no compact content to fall back to
+    pub fn shrink(&mut self) -> bool {
+        let Some(compact) = self.compact.take() else {
+            return false;
+        };
+        self.content = compact;
+        true
+    }
+
+    pub fn highlighted(mut self) -> Self {
+        self.style = self.style.bg_color(self.palette.cyan).underline();
+        self
+    }
+
+    // INFO: curly underline in `color`, drawn with `extended_sgr` only
+    pub fn accent(mut self, color: Option<Color>) -> Self {
+        let effects = self.style.get_effects().insert(Effects::CURLY_UNDERLINE);
+        self.style = self.style.effects(effects).underline_color(color);
+        self
+    }
+
+    // INFO: drawn with `extended_sgr` only
+    pub fn struck(mut self) -> Self {
+        self.style = self.style.strikethrough();
+        self
+    }
+
+    pub fn flashed(mut self) -> Self {
+        self.style = self
+            .style
+            .fg_color(self.palette.black)
+            .bg_color(self.palette.white)
+            .bold();
+        self
+    }
+
+    // INFO: `level` is how many times over the staleness threshold - dimmed from the first,
+    // receding into the bar background from the fourth
+    pub fn faded(mut self, level: u32) -> Self {
+        match level {
+            0 => {}
+            1..=3 => self.style = self.style.dimmed(),
+            _ => {
+                self.style = self
+                    .style
+                    .fg_color(self.palette.gray)
+                    .bg_color(self.palette.bg)
+                    .dimmed()
+            }
+        }
+        self
+    }
+}
+
+// NOTE: layout pass over the segments of a group - neighbours share a single transition glyph,
+// a thin divider when they share a background, instead of each one drawing both of its ends
+// against the bar. Already rendered segments keep their own ends and break the chain.
+pub fn join<'a>(segments: impl IntoIterator<Item = &'a mut Segment>) {
+    let mut previous: Option<&mut Segment> = None;
+    for segment in segments {
+        if segment.raw {
+            if !segment.content.to_string().is_empty() {
+                previous = None;
+            }
+            continue;
+        }
+        if let Some(previous) = previous.take() {
+            previous.next = Some(segment.style.get_bg_color());
+            segment.joined = true;
+        }
+        previous = Some(segment);
+    }
+}
+
+// INFO: thin variant of a separator glyph
+fn divider(glyph: &'static str) -> &'static str {
+    match glyph {
+        "" => "",
+        "" => "",
+        "" => "",
+        "" => "",
+        "" => "",
+        "" => "",
+        _ => glyph,
+    }
 }
 
-impl Default for Segment {
-    fn default() -> Self {
+impl Segment {
+    fn base(palette: &Palette) -> Self {
+        let (begin, end) = SEPARATOR.with(Cell::get);
+        let (padding_left, padding_right) = PADDING.with(Cell::get);
         Segment {
-            content: Box::new(""),
-            style: AnsiStyle::new().fg_color(*GRAY).bg_color(*BG),
+            content: Rc::new(""),
+            compact: None,
+            style: AnsiStyle::new().fg_color(palette.gray).bg_color(palette.bg),
+            priority: DEFAULT_PRIORITY,
 
             min_content_width: 0,
             max_content_width: 32,
+            truncate: Truncate::Right,
+            align: ALIGN.with(Cell::get),
 
-            padding_left: " ",
-            padding_right: " ",
+            padding_left,
+            padding_right,
 
-            begin: "",
-            end: "",
+            begin,
+            end,
+            mirrored: false,
+            next: None,
+            joined: false,
+            raw: false,
+            palette: *palette,
         }
     }
 }
 
 impl Display for Segment {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut content = self.content.to_string();
-        if content.graphemes(true).count() > self.max_content_width {
-            content = format!(
-                "{}...",
-                content
-                    .graphemes(true)
-                    .take(self.max_content_width - 3)
-                    .collect::<String>()
-            );
-        }
-
-        let begin_style = AnsiStyle::new()
-            .bg_color(self.style.get_bg_color())
-            .fg_color(*BG)
-            .render();
-        let end_style = AnsiStyle::new()
-            .fg_color(self.style.get_bg_color())
-            .bg_color(*BG)
-            .render();
-        let reset = self.style.render_reset();
-        let style = self.style.render();
-
-        write!(
-            f,
-            "{begin_style}{begin}{style}{padding_left}{content:^width$}{padding_right}{reset}{end_style}{end}",
-            width = self.min_content_width,
-            padding_left = self.padding_left,
-            padding_right = self.padding_right,
-            begin = self.begin,
-            end = self.end,
-        )
+        write!(f, "{}", self.render(usize::MAX).0)
+    }
+}
+
+impl RenderWidth for Segment {
+    // INFO: the compact content once the full one doesn't fit, cut down to the cells left after
+    // that - nothing when not even the separators and the padding fit
+    fn render(&self, max_cells: usize) -> (String, usize) {
+        let content = self.content.to_string();
+        if self.raw {
+            let width = content.display_len();
+            return (content, width);
+        }
+        let full = self.layout(content, self.min_content_width, self.max_content_width);
+        if full.1 <= max_cells {
+            return full;
+        }
+        let content = match &self.compact {
+            Some(compact) => {
+                let compact = compact.to_string();
+                let shrunk = self.layout(
+                    compact.clone(),
+                    self.min_content_width,
+                    self.max_content_width,
+                );
+                if shrunk.1 <= max_cells {
+                    return shrunk;
+                }
+                compact
+            }
+            None => self.content.to_string(),
+        };
+        let (_, chrome) = self.layout(String::new(), 0, 0);
+        if chrome >= max_cells {
+            return (String::new(), 0);
+        }
+        let cells = (max_cells - chrome).min(self.max_content_width);
+        self.layout(content, 0, cells)
+    }
+}
+
+impl Segment {
+    fn layout(
+        &self,
+        mut content: String,
+        min_content_width: usize,
+        max_content_width: usize,
+    ) -> (String, usize) {
+        // NOTE: counted in terminal cells and cut by grapheme clusters, `:^` would count chars
+        let mut width = crate::width(&content);
+        if width > max_content_width {
+            let ellipsis = ELLIPSIS.with_borrow(Clone::clone);
+            let ellipsis_width = crate::width(&ellipsis);
+            let cells = max_content_width.saturating_sub(ellipsis_width);
+            let (truncated, used) = match self.truncate {
+                Truncate::Right => {
+                    let (head, used) = truncate(&content, cells);
+                    (format!("{head}{ellipsis}"), used)
+                }
+                Truncate::Left => {
+                    let (tail, used) = truncate_start(&content, cells);
+                    (format!("{ellipsis}{tail}"), used)
+                }
+                Truncate::Middle => {
+                    let (head, head_used) = truncate(&content, cells - cells / 2);
+                    let (tail, tail_used) = truncate_start(&content, cells / 2);
+                    (format!("{head}{ellipsis}{tail}"), head_used + tail_used)
+                }
+            };
+            content = truncated;
+            width = used + ellipsis_width;
+        }
+        let padding = min_content_width.saturating_sub(width);
+        let (align_left, align_right) = match self.align {
+            Align::Left => (0, padding),
+            Align::Center => (padding / 2, padding - padding / 2),
+            Align::Right => (padding, 0),
+        };
+
+        let bg = self.style.get_bg_color();
+        let inverted = AnsiStyle::new().bg_color(bg).fg_color(self.palette.bg);
+        let colored = AnsiStyle::new().fg_color(bg).bg_color(self.palette.bg);
+        // NOTE: mirrored segments start with a glyph in their color and end with a notch
+        let (begin_style, end_style) = if self.mirrored {
+            (colored, inverted)
+        } else {
+            (inverted, colored)
+        };
+        let mut style = self.style;
+        if !ANIMATIONS.with(Cell::get) {
+            style = style.effects(style.get_effects().remove(Effects::BLINK));
+        }
+        if !EXTENDED_SGR.with(Cell::get) {
+            let extended = Effects::CURLY_UNDERLINE | Effects::STRIKETHROUGH;
+            style = style
+                .effects(style.get_effects().remove(extended))
+                .underline_color(None);
+        }
+        let reset = style.render_reset();
+        let style = style.render();
+
+        let begin = if self.joined { "" } else { self.begin };
+        let end = match self.next {
+            Some(next) if next == bg => divider(self.end),
+            _ => self.end,
+        };
+        let end_style = match self.next {
+            None => end_style.render().to_string(),
+            Some(next) if next == bg => style.to_string(),
+            Some(next) => {
+                let transition = if self.mirrored {
+                    AnsiStyle::new().fg_color(next).bg_color(bg)
+                } else {
+                    AnsiStyle::new().fg_color(bg).bg_color(next)
+                };
+                transition.render().to_string()
+            }
+        };
+        let end_reset = if self.next.is_some() {
+            Reset.render().to_string()
+        } else {
+            String::new()
+        };
+        let begin_style = if self.joined {
+            String::new()
+        } else {
+            begin_style.render().to_string()
+        };
+
+        let (left, right) = (
+            self.padding_left + align_left,
+            align_right + self.padding_right,
+        );
+        let line = format!(
+            "{begin_style}{begin}{style}{}{content}{}{reset}{end_style}{end}{end_reset}",
+            " ".repeat(left),
+            " ".repeat(right),
+        );
+        let cells = crate::width(begin) + left + width + right + crate::width(end);
+        (line, cells)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FAMILY: &str = "\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467}\u{200d}\u{1f466}";
+
+    fn plain(segment: &Segment) -> String {
+        strip_ansi_escapes::strip_str(segment.to_string())
+    }
+
+    #[test]
+    fn truncates_without_splitting_clusters() {
+        let content = format!("{FAMILY}{FAMILY}{FAMILY}{FAMILY}{FAMILY}");
+        let segment =
+            Segment::new(Box::new(content), AnsiStyle::new(), &Palette::default()).max_width(5);
+        assert!(plain(&segment).contains(&format!(" {FAMILY}... ")));
+    }
+
+    #[test]
+    fn centers_by_terminal_cells() {
+        let segment =
+            Segment::new(Box::new(FAMILY), AnsiStyle::new(), &Palette::default()).min_width(5);
+        assert!(plain(&segment).contains(&format!("  {FAMILY}   ")));
+        assert_eq!(
+            segment.display_len(),
+            Segment::new(Box::new("x"), AnsiStyle::new(), &Palette::default())
+                .min_width(5)
+                .display_len()
+        );
+    }
+
+    #[test]
+    fn counts_wide_characters_twice() {
+        let segment =
+            Segment::new(Box::new("漢字"), AnsiStyle::new(), &Palette::default()).min_width(6);
+        assert!(plain(&segment).contains("  漢字  "));
+        assert_eq!("漢字".display_len(), 4);
+    }
+
+    #[test]
+    fn aligns_without_padding() {
+        let segment = Segment::new(Box::new("ab"), AnsiStyle::new(), &Palette::default())
+            .min_width(5)
+            .padding(0, 0)
+            .align(Align::Left);
+        let (begin, end) = separator();
+        assert!(plain(&segment).contains(&format!("{begin}ab   {end}")));
+        let segment = Segment::new(Box::new("ab"), AnsiStyle::new(), &Palette::default())
+            .min_width(5)
+            .padding(1, 0)
+            .align(Align::Right);
+        assert!(plain(&segment).contains(&format!("{begin}    ab{end}")));
+    }
+
+    #[test]
+    fn mirrors_with_separator_right() {
+        let segment =
+            Segment::new(Box::new("ab"), AnsiStyle::new(), &Palette::default()).mirrored();
+        assert!(!segment.mirrored);
+        set_separator_right(Some("round"));
+        let segment =
+            Segment::new(Box::new("ab"), AnsiStyle::new(), &Palette::default()).mirrored();
+        assert!(plain(&segment).contains("\u{e0b6} ab \u{e0b6}"));
+    }
+
+    #[test]
+    fn joins_neighbours() {
+        let style = AnsiStyle::new().bg_color(Some(AnsiColor::Blue.into()));
+        let mut segments = [
+            Segment::new(Box::new("a"), style, &Palette::default()),
+            Segment::empty(),
+            Segment::new(Box::new("b"), style, &Palette::default()),
+            Segment::new(Box::new("c"), AnsiStyle::new(), &Palette::default()),
+        ];
+        join(&mut segments);
+        let line: String = segments.iter().map(plain).collect();
+        assert_eq!(line, "\u{e0b0} a \u{e0b1} b \u{e0b0} c \u{e0b0}");
+    }
+
+    #[test]
+    fn truncates_from_either_side() {
+        let path = "~/projects/statusline/src";
+        let left = Segment::new(Box::new(path), AnsiStyle::new(), &Palette::default())
+            .max_width(10)
+            .truncate(Truncate::Left);
+        assert!(plain(&left).contains(" ...ine/src "));
+        let middle = Segment::new(Box::new(path), AnsiStyle::new(), &Palette::default())
+            .max_width(10)
+            .truncate(Truncate::Middle);
+        assert!(plain(&middle).contains(" ~/pr...src "));
+    }
+
+    #[test]
+    fn tiny_max_width_does_not_panic() {
+        let segment =
+            Segment::new(Box::new("abcdef"), AnsiStyle::new(), &Palette::default()).max_width(1);
+        assert!(plain(&segment).contains(" ... "));
+    }
+
+    #[test]
+    fn single_cell_tab_indices() {
+        let indices = |style| {
+            set_tab_index(style);
+            [9, 10, 15, 16, 35, 36].map(|x| tab_index(x - 1))
+        };
+        assert_eq!(indices("hex"), ["9", "a", "f", "+", "+", "+"]);
+        assert_eq!(indices("alpha"), ["9", "a", "f", "g", "z", "+"]);
+        assert_eq!(indices("decimal"), ["9", "10", "15", "16", "35", "36"]);
     }
 }
@@ -0,0 +1,51 @@
+use std::{collections::VecDeque, fmt::Display};
+
+const BLOCKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+// NOTE: history of the last `width` samples of a numeric segment
+pub struct Sparkline {
+    samples: VecDeque<f64>,
+    width: usize,
+}
+
+impl Display for Sparkline {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let min = self.samples.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = self
+            .samples
+            .iter()
+            .copied()
+            .fold(f64::NEG_INFINITY, f64::max);
+        let range = max - min;
+
+        for sample in &self.samples {
+            // INFO: flat history renders in the middle instead of at the bottom
+            let level = if range > 0.0 {
+                ((sample - min) / range * (BLOCKS.len() - 1) as f64).round() as usize
+            } else {
+                BLOCKS.len() / 2
+            };
+            write!(f, "{}", BLOCKS[level.min(BLOCKS.len() - 1)])?;
+        }
+        Ok(())
+    }
+}
+
+impl Sparkline {
+    pub fn new(width: usize) -> Self {
+        Self {
+            samples: VecDeque::with_capacity(width),
+            width,
+        }
+    }
+
+    pub fn push(&mut self, sample: f64) {
+        if !sample.is_finite() || self.width == 0 {
+            return;
+        }
+        if self.samples.len() == self.width {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+}
@@ -0,0 +1,200 @@
+use std::ops::Range;
+
+use anstyle::Style as AnsiStyle;
+
+use crate::{budget::Budget, caps, fill_styled, DisplayExt, Palette, RenderWidth, Segment};
+
+// NOTE: rendered elements by name in the left, center and right group of a single line
+pub struct Statusline {
+    groups: [Vec<(&'static str, String)>; 3],
+    fill: AnsiStyle,
+    offset: usize,
+}
+
+pub struct StatuslineBuilder {
+    groups: [Vec<(&'static str, String)>; 3],
+    fill: AnsiStyle,
+    offset: usize,
+}
+
+impl Default for StatuslineBuilder {
+    fn default() -> Self {
+        Self {
+            groups: Default::default(),
+            fill: AnsiStyle::new()
+                .fg_color(Palette::default().gray)
+                .bg_color(Palette::default().bg),
+            offset: 0,
+        }
+    }
+}
+
+impl StatuslineBuilder {
+    pub fn left(mut self, elements: impl IntoIterator<Item = (&'static str, String)>) -> Self {
+        self.groups[0].extend(elements);
+        self
+    }
+
+    pub fn center(mut self, elements: impl IntoIterator<Item = (&'static str, String)>) -> Self {
+        self.groups[1].extend(elements);
+        self
+    }
+
+    pub fn right(mut self, elements: impl IntoIterator<Item = (&'static str, String)>) -> Self {
+        self.groups[2].extend(elements);
+        self
+    }
+
+    // INFO: style of the empty space between the groups
+    pub fn fill(mut self, style: AnsiStyle) -> Self {
+        self.fill = style;
+        self
+    }
+
+    // INFO: columns scrolled past on the left of a line wider than the terminal
+    pub fn offset(mut self, offset: usize) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    pub fn build(self) -> Statusline {
+        Statusline {
+            groups: self.groups,
+            fill: self.fill,
+            offset: self.offset,
+        }
+    }
+}
+
+impl Statusline {
+    pub fn builder() -> StatuslineBuilder {
+        StatuslineBuilder::default()
+    }
+
+    // INFO: the line along with the columns occupied by each element, empty space is named `fill`
+    pub fn render(mut self, cols: usize) -> (String, Vec<(&'static str, Range<usize>)>) {
+        let groups = &mut self.groups;
+
+        // NOTE: empty elements don't take part in the caps
+        if let Some((_, s)) = groups.iter_mut().flatten().find(|(_, s)| !s.is_empty()) {
+            *s = caps::left(std::mem::take(s));
+        }
+        if let Some((_, s)) = groups
+            .iter_mut()
+            .flatten()
+            .rev()
+            .find(|(_, s)| !s.is_empty())
+        {
+            *s = caps::right(std::mem::take(s));
+        }
+
+        // NOTE: fill empty space - around the center group when there is one, between the left
+        // and the right group otherwise
+        let widths = groups
+            .each_ref()
+            .map(|x| x.iter().map(|(_, s)| s.display_len()).sum::<usize>());
+        let gap = cols.saturating_sub(widths.iter().sum());
+        let left_gap = if widths[1] == 0 {
+            gap
+        } else {
+            (cols.saturating_sub(widths[1]) / 2)
+                .saturating_sub(widths[0])
+                .min(gap)
+        };
+        let gaps = [0, left_gap, gap - left_gap];
+
+        let mut regions = Vec::new();
+        let mut budget = Budget::new(cols).skip(self.offset);
+        let mut chars = 0;
+        for (group, gap) in groups.iter().zip(gaps) {
+            if gap > 0 {
+                regions.push(("fill", chars..chars + gap));
+                chars += gap;
+                budget.emit(&fill_styled(gap, "-", self.fill));
+            }
+            for (name, s) in group {
+                let width = s.display_len();
+                regions.push((*name, chars..chars + width));
+                chars += width;
+                budget.emit(s);
+            }
+        }
+        // INFO: regions scrolled out of view end up empty
+        let offset = self.offset;
+        let regions = regions
+            .into_iter()
+            .map(|(name, x)| {
+                (
+                    name,
+                    x.start.saturating_sub(offset)..x.end.saturating_sub(offset),
+                )
+            })
+            .filter(|(_, x)| !x.is_empty())
+            .collect();
+        (budget.finish(), regions)
+    }
+}
+
+// NOTE: least important segments are shrunk, then dropped, until the groups fit in `width` -
+// segments of the highest priority are never dropped, they are cut to the cells left instead
+pub fn fit(groups: &mut [Vec<(&'static str, Segment)>], width: usize) {
+    let width_of = |s: &Segment| s.render(usize::MAX).1;
+    let mut used: usize = groups.iter().flatten().map(|(_, s)| width_of(s)).sum();
+    let mut order: Vec<(usize, usize)> = groups
+        .iter()
+        .enumerate()
+        .flat_map(|(group, x)| (0..x.len()).map(move |index| (group, index)))
+        .collect();
+    order.sort_by_key(|(group, index)| groups[*group][*index].1.get_priority());
+
+    for pass in [Pass::Shrink, Pass::Drop, Pass::Cut] {
+        for (group, index) in &order {
+            if used <= width {
+                return;
+            }
+            let segment = &mut groups[*group][*index].1;
+            let kept = segment.get_priority() == u8::MAX;
+            let before = width_of(segment);
+            match pass {
+                Pass::Shrink if !kept => {
+                    segment.shrink();
+                }
+                Pass::Drop if !kept => *segment = Segment::empty(),
+                Pass::Cut if kept => {
+                    let cells = before.saturating_sub(used - width);
+                    let (cut, _) = segment.render(cells);
+                    *segment = Segment::raw(Box::new(cut));
+                }
+                _ => continue,
+            }
+            used = used - before + width_of(segment);
+        }
+    }
+}
+
+enum Pass {
+    Shrink,
+    Drop,
+    Cut,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cuts_what_cannot_be_dropped() {
+        let wide = Segment::new(
+            Box::new("x".repeat(40)),
+            AnsiStyle::new(),
+            &Palette::default(),
+        )
+        .priority(u8::MAX);
+        let other = Segment::new(Box::new("clock"), AnsiStyle::new(), &Palette::default());
+        let mut groups = [vec![("diagnostics", wide)], vec![], vec![("clock", other)]];
+        fit(&mut groups, 20);
+        let used: usize = groups.iter().flatten().map(|(_, s)| s.display_len()).sum();
+        assert!(used <= 20);
+        assert!(groups[0][0].1.display_len() > 0);
+    }
+}
@@ -1,71 +1,497 @@
-use std::fmt::Display;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    ops::Range,
+};
 
-use zellij_tile::prelude::TabInfo;
+use chrono::{DateTime, Local};
+use zellij_tile::prelude::*;
 
-use crate::{DisplayExt, Segment};
+use anstyle::Style as AnsiStyle;
+
+use crate::{
+    sanitize, segment::glyph, time, widget::Widget, DisplayExt, Palette, RenderWidth, Segment,
+};
 
 #[derive(Default)]
 pub struct Tabs {
-    pub max_width: usize,
+    // NOTE: shows the command of the focused pane in place of a default active tab name
+    pub suggest: bool,
+    // NOTE: shown until zellij sends the first tabs
+    pub placeholder: String,
 
     full: (usize, String),
     compact: (usize, String),
     fold: (usize, String),
+    active: String,
+
+    inner: Vec<TabInfo>,
+    // NOTE: tab highlighted by keyboard navigation
+    selected: Option<usize>,
+    // NOTE: names of tabs protected from closing, carried over when a tab is renamed in place
+    protected: BTreeSet<String>,
+    // NOTE: percentage done of jobs watched in tabs by tab name
+    progress: BTreeMap<String, f64>,
+    // NOTE: program of the focused pane by tab position
+    commands: BTreeMap<usize, String>,
+    // NOTE: query typed into the tab filter, `None` while every tab is shown
+    filter: Option<String>,
+    // NOTE: seconds without focus or activity after which tabs fade, `0` keeps them as they are
+    pub stale_after: f64,
+    // NOTE: last focus or change of the running command by tab name
+    last_active: BTreeMap<String, DateTime<Local>>,
+    // NOTE: the active tab stands out for a moment after being switched to from elsewhere
+    flashing: bool,
+    // NOTE: columns of every tab within the full, compact and folded representations
+    spans: [Vec<(usize, Range<usize>)>; 3],
+    // NOTE: colors the tabs are rendered ahead with, handed over by `refresh`
+    palette: Palette,
 }
 
-impl Display for Tabs {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let content = if self.max_width > self.full.0 {
-            &self.full.1
-        } else if self.max_width > self.compact.0 {
-            &self.compact.1
-        } else {
-            &self.fold.1
+// NOTE: cells of the progress bar drawn after the tab name
+const PROGRESS_WIDTH: usize = 4;
+
+impl Widget for Tabs {
+    fn interested_events(&self) -> Vec<EventType> {
+        vec![
+            EventType::TabUpdate,
+            EventType::PaneUpdate,
+            EventType::Timer,
+        ]
+    }
+
+    // INFO: keeps the keyboard selection while it still points to an existing tab, returns
+    // `true` only when the rendered tabs changed
+    fn update(&mut self, event: &Event) -> bool {
+        if let Event::PaneUpdate(manifest) = event {
+            let commands = manifest
+                .panes
+                .iter()
+                .filter_map(|(tab, panes)| {
+                    let pane = panes.iter().find(|x| x.is_focused && !x.is_plugin)?;
+                    Some((*tab, program(pane)?))
+                })
+                .collect();
+            if self.commands == commands {
+                return false;
+            }
+            let now = time::now();
+            for tab in &self.inner {
+                if self.commands.get(&tab.position) != commands.get(&tab.position) {
+                    self.last_active.insert(tab.name.clone(), now);
+                }
+            }
+            self.commands = commands;
+            return self.render_tabs();
+        }
+        // INFO: fading is only re-evaluated when time passes
+        if let Event::Timer(_) = event {
+            return self.stale_after > 0.0 && self.render_tabs();
+        }
+        let Event::TabUpdate(inner) = event else {
+            return false;
         };
-        write!(f, "{content}")
+        self.selected = self.selected.filter(|x| *x < inner.len());
+        // INFO: a renamed tab keeps its position while its old name is gone and the new one unseen
+        let renamed: Vec<(String, String)> = self
+            .inner
+            .iter()
+            .filter(|old| self.protected.contains(&old.name))
+            .filter(|old| !inner.iter().any(|x| x.name == old.name))
+            .filter_map(|old| {
+                let new = inner.iter().find(|x| x.position == old.position)?;
+                let unseen = !self.inner.iter().any(|x| x.name == new.name);
+                unseen.then(|| (old.name.clone(), new.name.clone()))
+            })
+            .collect();
+        for (old, new) in renamed {
+            self.protected.remove(&old);
+            self.protected.insert(new);
+        }
+        self.inner = inner.clone();
+        // INFO: jobs of closed tabs are forgotten
+        self.progress
+            .retain(|name, _| inner.iter().any(|x| &x.name == name));
+        // INFO: tabs seen for the first time count as active
+        let now = time::now();
+        self.last_active
+            .retain(|name, _| inner.iter().any(|x| &x.name == name));
+        for tab in inner {
+            if tab.active {
+                self.last_active.insert(tab.name.clone(), now);
+            } else {
+                self.last_active.entry(tab.name.clone()).or_insert(now);
+            }
+        }
+        self.render_tabs()
+    }
+
+    // INFO: every tab written out, `RenderWidth` picks the representation fitting the bar
+    fn render(&self, _palette: &Palette) -> Segment {
+        Segment::raw(Box::new(RenderWidth::render(self, usize::MAX).0))
+    }
+}
+
+impl RenderWidth for Tabs {
+    // INFO: every tab written out when there's room, compact ones next, folded ones otherwise
+    fn render(&self, max_cells: usize) -> (String, usize) {
+        // NOTE: zellij may send an empty tab list while the session starts or a layout is applied
+        if self.inner.is_empty() {
+            let placeholder = Segment::new(
+                Box::new(self.placeholder.clone()),
+                AnsiStyle::new()
+                    .fg_color(self.palette.black)
+                    .bg_color(self.palette.gray)
+                    .italic(),
+                &self.palette,
+            );
+            return placeholder.render(max_cells);
+        }
+
+        let (width, content) = match self.variant(max_cells) {
+            "full" => &self.full,
+            "compact" => &self.compact,
+            _ => &self.fold,
+        };
+        (content.clone(), *width)
     }
 }
 
 impl Tabs {
-    pub fn new(inner: Vec<TabInfo>) -> Self {
-        let full: String = inner
+    // INFO: representation picked by `render` for `max_cells`, shown by the debug overlay
+    pub fn variant(&self, max_cells: usize) -> &'static str {
+        if self.inner.is_empty() {
+            "placeholder"
+        } else if max_cells > self.full.0 {
+            "full"
+        } else if max_cells > self.compact.0 {
+            "compact"
+        } else {
+            "fold"
+        }
+    }
+
+    // INFO: only the active tab, used by the zen layout
+    pub fn active(&self) -> &str {
+        &self.active
+    }
+
+    // INFO: position of the tab drawn `column` cells into the representation `render` picked for
+    // `max_cells`
+    pub fn tab_at(&self, max_cells: usize, column: usize) -> Option<usize> {
+        let spans = match self.variant(max_cells) {
+            "full" => &self.spans[0],
+            "compact" => &self.spans[1],
+            "fold" => &self.spans[2],
+            _ => return None,
+        };
+        spans
+            .iter()
+            .find(|(_, x)| x.contains(&column))
+            .map(|(position, _)| *position)
+    }
+
+    // INFO: width of the folded tabs, the least they can take
+    pub fn min_width(&self) -> usize {
+        self.fold.0
+    }
+
+    // INFO: width of every tab written out, the most they can take
+    pub fn max_width(&self) -> usize {
+        self.full.0
+    }
+
+    pub fn count(&self) -> usize {
+        self.inner.len()
+    }
+
+    pub fn active_position(&self) -> Option<usize> {
+        self.active_tab().map(|x| x.position)
+    }
+
+    pub fn infos(&self) -> &[TabInfo] {
+        &self.inner
+    }
+
+    pub fn selected(&self) -> Option<usize> {
+        self.selected
+    }
+
+    pub fn select(&mut self, selected: Option<usize>) {
+        self.selected = selected;
+        self.render_tabs();
+    }
+
+    pub fn filter(&self) -> Option<&str> {
+        self.filter.as_deref()
+    }
+
+    pub fn set_filter(&mut self, filter: Option<String>) {
+        self.filter = filter;
+        self.render_tabs();
+    }
+
+    // INFO: tab focused by enter while filtering
+    pub fn first_match(&self) -> Option<usize> {
+        let query = self.filter.as_deref()?;
+        self.inner
             .iter()
-            .map(Segment::new_tab)
-            .map(|x| x.to_string())
+            .find(|x| fuzzy(&x.name, query))
+            .map(|x| x.position)
+    }
+
+    pub fn is_protected(&self, position: usize) -> bool {
+        self.inner
+            .get(position)
+            .is_some_and(|x| self.protected.contains(&x.name))
+    }
+
+    // INFO: `None` toggles the active tab
+    pub fn toggle_protected(&mut self, name: Option<String>) {
+        let Some(name) = name.or_else(|| self.active_tab().map(|x| x.name.clone())) else {
+            return;
+        };
+        if !self.protected.remove(&name) {
+            self.protected.insert(name);
+        }
+        self.render_tabs();
+    }
+
+    // INFO: `None` tab is the active one, `None` percent clears the bar
+    pub fn set_progress(&mut self, name: Option<String>, percent: Option<f64>) {
+        let Some(name) = name.or_else(|| self.active_tab().map(|x| x.name.clone())) else {
+            return;
+        };
+        match percent {
+            Some(percent) => self.progress.insert(name, percent.clamp(0.0, 100.0)),
+            None => self.progress.remove(&name),
+        };
+        self.render_tabs();
+    }
+
+    // INFO: `(position, name)` offered for the active tab while it has a default name
+    pub fn suggestion(&self) -> Option<(usize, &str)> {
+        let tab = self.active_tab().filter(|_| self.suggest)?;
+        let number = tab.name.strip_prefix("Tab #")?;
+        if number.is_empty() || !number.chars().all(|x| x.is_ascii_digit()) {
+            return None;
+        }
+        let command = self.commands.get(&tab.position)?;
+        Some((tab.position, command))
+    }
+
+    // INFO: returns `true` when the rendered tabs changed
+    pub fn set_flashing(&mut self, flashing: bool) -> bool {
+        self.flashing = flashing;
+        self.render_tabs()
+    }
+
+    // INFO: segments are rendered ahead, colors changed since have to be picked up
+    pub fn refresh(&mut self, palette: &Palette) {
+        self.palette = *palette;
+        self.render_tabs();
+    }
+
+    fn active_tab(&self) -> Option<&TabInfo> {
+        self.inner.iter().find(|x| x.active)
+    }
+
+    // INFO: returns `true` when any of the rendered tabs changed
+    fn render_tabs(&mut self) -> bool {
+        let previous = (
+            self.full.1.clone(),
+            self.compact.1.clone(),
+            self.fold.1.clone(),
+        );
+        if self.inner.is_empty() {
+            self.full = Default::default();
+            self.compact = Default::default();
+            self.fold = Default::default();
+            self.spans = Default::default();
+            self.active = Default::default();
+            return previous != Default::default();
+        }
+        let palette = self.palette;
+
+        let suggestion = self
+            .suggestion()
+            .map(|(position, x)| (position, x.to_string()));
+        let inner: Vec<TabInfo> = self
+            .inner
+            .iter()
+            .filter(|tab| self.filter.as_deref().is_none_or(|x| fuzzy(&tab.name, x)))
+            .map(|tab| {
+                let mut tab = tab.clone();
+                let mut name = sanitize::text(&tab.name);
+                // INFO: ghost name in italics, clicking the tabs applies it
+                if let Some((_, command)) = suggestion.as_ref().filter(|x| x.0 == tab.position) {
+                    name = format!("\x1b[3m{}?\x1b[23m", sanitize::text(command));
+                }
+                if let Some(percent) = self.progress.get(&tab.name) {
+                    name = format!("{name} {}", progress_bar(*percent));
+                }
+                tab.name = if self.protected.contains(&tab.name) {
+                    format!("{} {name}", glyph("", "[P]"))
+                } else {
+                    name
+                };
+                tab
+            })
+            .collect();
+        let highlighted = match self.filter {
+            Some(_) => self.first_match(),
+            None => self.selected,
+        };
+        let now = time::now();
+        let highlight = |tab: &TabInfo, segment: Segment| {
+            if Some(tab.position) == highlighted {
+                segment.highlighted()
+            } else if tab.active && self.flashing {
+                segment.flashed()
+            } else if tab.active || self.stale_after <= 0.0 {
+                segment
+            } else {
+                let idle = self
+                    .inner
+                    .get(tab.position)
+                    .and_then(|x| self.last_active.get(&x.name))
+                    .map_or(0.0, |x| (now - *x).num_seconds() as f64);
+                segment.faded((idle / self.stale_after) as u32)
+            }
+        };
+
+        let full: Vec<_> = inner
+            .iter()
+            .map(|x| {
+                (
+                    Some(x.position),
+                    highlight(x, Segment::new_tab(x, &palette)).to_string(),
+                )
+            })
             .collect();
 
-        let compact: String = inner
+        let compact: Vec<_> = inner
             .iter()
-            .map(Segment::new_compact_tab)
-            .map(|x| x.to_string())
+            .map(|x| {
+                let segment = highlight(x, Segment::new_compact_tab(x, &palette));
+                (Some(x.position), segment.to_string())
+            })
             .collect();
 
+        let active = inner
+            .iter()
+            .find(|x| x.active)
+            .map(|x| Segment::new_tab(x, &palette).to_string())
+            .unwrap_or_default();
+
+        // INFO: matches are listed as they are behind the query, folding needs contiguous tabs
+        if let Some(query) = &self.filter {
+            let input = Segment::new(
+                Box::new(format!("/{query}▏")),
+                AnsiStyle::new()
+                    .fg_color(palette.black)
+                    .bg_color(palette.cyan),
+                &palette,
+            )
+            .to_string();
+            let full = lay_out([(None, input.clone())].into_iter().chain(full));
+            let compact = lay_out([(None, input)].into_iter().chain(compact));
+            self.full = (full.0.display_len(), full.0);
+            self.fold = (compact.0.display_len(), compact.0.clone());
+            self.compact = (compact.0.display_len(), compact.0);
+            self.spans = [full.1, compact.1.clone(), compact.1];
+            self.active = active;
+            return previous
+                != (
+                    self.full.1.clone(),
+                    self.compact.1.clone(),
+                    self.fold.1.clone(),
+                );
+        }
+
+        // INFO: ranges of folded tabs can't be told apart by a click, only the active tab can
         let last = inner.len() - 1;
         let fold = if let Some(active) = inner.iter().find(|x| x.active) {
-            let mut active_segment = Segment::new_tab(active).to_string();
+            let mut fold = Vec::new();
             if active.position != 0 {
-                active_segment = format!(
-                    "{}{active_segment}",
-                    Segment::new_range_tab(0..active.position - 1)
-                );
+                let range = Segment::new_range_tab(0..active.position - 1, &palette);
+                fold.push((None, range.to_string()));
             }
+            fold.push((
+                Some(active.position),
+                Segment::new_tab(active, &palette).to_string(),
+            ));
             if active.position != last {
-                active_segment = format!(
-                    "{active_segment}{}",
-                    Segment::new_range_tab(active.position + 1..last)
-                )
+                let range = Segment::new_range_tab(active.position + 1..last, &palette);
+                fold.push((None, range.to_string()));
             }
-            active_segment.to_string()
+            fold
         } else {
-            Segment::new_range_tab(0..last).to_string()
+            vec![(None, Segment::new_range_tab(0..last, &palette).to_string())]
         };
 
-        Self {
-            max_width: usize::MAX,
+        let (full, compact, fold) = (lay_out(full), lay_out(compact), lay_out(fold));
+        self.full = (full.0.display_len(), full.0);
+        self.compact = (compact.0.display_len(), compact.0);
+        self.fold = (fold.0.display_len(), fold.0);
+        self.spans = [full.1, compact.1, fold.1];
+        self.active = active;
+        previous
+            != (
+                self.full.1.clone(),
+                self.compact.1.clone(),
+                self.fold.1.clone(),
+            )
+    }
+}
 
-            full: (full.display_len(), full),
-            compact: (compact.display_len(), compact),
-            fold: (fold.display_len(), fold),
+// INFO: segments one after another, along with the columns of the ones standing for a single tab
+fn lay_out(
+    segments: impl IntoIterator<Item = (Option<usize>, String)>,
+) -> (String, Vec<(usize, Range<usize>)>) {
+    let mut line = String::new();
+    let mut spans = Vec::new();
+    let mut chars = 0;
+    for (position, segment) in segments {
+        let width = segment.display_len();
+        if let Some(position) = position {
+            spans.push((position, chars..chars + width));
         }
+        chars += width;
+        line.push_str(&segment);
+    }
+    (line, spans)
+}
+
+// INFO: characters of the query in order, not necessarily next to each other - `bld` matches
+// `build`
+fn fuzzy(name: &str, query: &str) -> bool {
+    let mut name = name.chars().flat_map(char::to_lowercase);
+    query
+        .chars()
+        .flat_map(char::to_lowercase)
+        .all(|x| name.any(|c| c == x))
+}
+
+// INFO: eighths of a cell through partial blocks - `██▌ `
+fn progress_bar(percent: f64) -> String {
+    const PARTIAL: [&str; 8] = ["", "▏", "▎", "▍", "▌", "▋", "▊", "▉"];
+    let eighths = (percent / 100.0 * (PROGRESS_WIDTH * 8) as f64).round() as usize;
+    let full = eighths / 8;
+    let partial = PARTIAL[eighths % 8];
+    let bar = format!("{}{partial}", "█".repeat(full));
+    let used = full + usize::from(!partial.is_empty());
+    format!("{bar}{}", " ".repeat(PROGRESS_WIDTH.saturating_sub(used)))
+}
+
+// INFO: program name of a command pane or of the title shells set while running a command,
+// default `Pane #1` titles and paths of idle shells have none
+fn program(pane: &PaneInfo) -> Option<String> {
+    let command = pane.terminal_command.as_deref().unwrap_or(&pane.title);
+    let word = command.split_whitespace().next()?;
+    if pane.terminal_command.is_none()
+        && (command.starts_with("Pane #") || word.starts_with(['~', '/']))
+    {
+        return None;
     }
+    Some(word.rsplit('/').next().unwrap_or(word).to_string())
 }
@@ -0,0 +1,73 @@
+use std::collections::BTreeMap;
+
+use anstyle::Style as AnsiStyle;
+
+use crate::{memo, widget::Widget, Palette, Segment};
+
+// NOTE: placeholders a `text` template can refer to - `{session} · {tab_count} tabs · {mode}`
+pub const VARIABLES: &[&str] = &["session", "mode", "tab", "tab_count", "clock", "cols"];
+
+// NOTE: user composed segment, the variables are filled in by `render_to_string` right before
+// every render
+#[derive(Default)]
+pub struct Text {
+    template: String,
+    pub vars: BTreeMap<&'static str, String>,
+}
+
+impl Widget for Text {
+    fn render(&self, palette: &Palette) -> Segment {
+        if self.template.is_empty() {
+            return Segment::empty();
+        }
+        Segment::new(
+            Box::new(interpolate(&self.template, &self.vars)),
+            AnsiStyle::new()
+                .fg_color(palette.black)
+                .bg_color(palette.gray),
+            palette,
+        )
+    }
+
+    fn state(&self) -> Option<u64> {
+        Some(memo::hash(&self.vars))
+    }
+}
+
+impl Text {
+    pub fn new(template: String) -> Self {
+        Self {
+            template,
+            vars: BTreeMap::new(),
+        }
+    }
+}
+
+// INFO: unknown placeholders are kept as they are, `config::parse` reports them
+pub fn interpolate(template: &str, vars: &BTreeMap<&'static str, String>) -> String {
+    let mut interpolated = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        interpolated += &rest[..start];
+        let name = &rest[start + 1..start + len];
+        match vars.get(name) {
+            Some(value) => interpolated += value,
+            None => interpolated += &rest[start..=start + len],
+        }
+        rest = &rest[start + len + 1..];
+    }
+    interpolated + rest
+}
+
+pub fn unknown(template: &str) -> Vec<&str> {
+    template
+        .split('{')
+        .skip(1)
+        .filter_map(|x| x.split_once('}'))
+        .map(|(name, _)| name)
+        .filter(|x| !VARIABLES.contains(x))
+        .collect()
+}
@@ -0,0 +1,177 @@
+use std::{collections::BTreeMap, fs};
+
+use anstyle::{Ansi256Color, Color, RgbColor};
+use kdl::{KdlDocument, KdlNode};
+use zellij_tile::prelude::{Palette as ZellijPalette, PaletteColor as ZellijColor};
+
+use crate::{config_file, palette::Palette, parse_color, Segment, Truncate};
+
+// NOTE: suffixes of per element color keys - `mode_bg = "blue"`, `clock_fg = "black"`
+pub const FG_SUFFIX: &str = "_fg";
+pub const BG_SUFFIX: &str = "_bg";
+// NOTE: suffix of per element truncation keys - `workspace_truncate = "left"`
+pub const TRUNCATE_SUFFIX: &str = "_truncate";
+
+// NOTE: colors of elements overridden through the configuration, applied on top of whatever the
+// widget renders. Widgets made of several segments (tabs, custom, buttons) keep their own colors.
+#[derive(Default)]
+pub struct Theme {
+    colors: BTreeMap<String, (Option<Color>, Option<Color>)>,
+    truncation: BTreeMap<String, Truncate>,
+}
+
+impl Theme {
+    pub fn new(config: &BTreeMap<String, String>) -> Self {
+        let mut colors: BTreeMap<String, (Option<Color>, Option<Color>)> = BTreeMap::new();
+        let mut truncation = BTreeMap::new();
+        for (key, value) in config {
+            let color = parse_color(value);
+            if let Some(name) = key.strip_suffix(FG_SUFFIX) {
+                colors.entry(name.to_string()).or_default().0 = color;
+            } else if let Some(name) = key.strip_suffix(BG_SUFFIX) {
+                colors.entry(name.to_string()).or_default().1 = color;
+            } else if let Some(name) = key.strip_suffix(TRUNCATE_SUFFIX) {
+                if let Ok(truncate) = value.parse() {
+                    truncation.insert(name.to_string(), truncate);
+                }
+            }
+        }
+        Self { colors, truncation }
+    }
+
+    pub fn apply(&self, name: &str, mut segment: Segment) -> Segment {
+        if let Some(truncate) = self.truncation.get(name) {
+            segment = segment.truncate(*truncate);
+        }
+        match self.colors.get(name) {
+            Some((fg, bg)) => segment.themed(*fg, *bg),
+            None => segment,
+        }
+    }
+}
+
+// NOTE: zellij themes are KDL files like
+// themes {
+//     catppuccin {
+//         fg "#cdd6f4"
+//         red 243 139 168
+//         ...
+//     }
+// }
+// Only the first theme of the file is read, colors missing in it keep their defaults.
+pub fn load(path: &str) -> Result<Palette, String> {
+    let content = fs::read_to_string(host_path(path)?).map_err(|err| format!("{path}: {err}"))?;
+    let document: KdlDocument = content.parse().map_err(|err| format!("{path}: {err}"))?;
+    let theme = document
+        .get("themes")
+        .and_then(KdlNode::children)
+        .and_then(|x| x.nodes().first())
+        .ok_or_else(|| format!("{path}: no theme in `themes`"))?;
+
+    let mut palette = Palette::default();
+    let mut found = false;
+    for node in theme.children().map(KdlDocument::nodes).unwrap_or_default() {
+        let values: Vec<String> = node
+            .entries()
+            .iter()
+            .filter(|x| x.name().is_none())
+            .map(|x| config_file::text(x.value()))
+            .collect();
+        let values: Vec<&str> = values.iter().map(String::as_str).collect();
+        let Some(color) = parse_theme_color(&values) else {
+            continue;
+        };
+
+        let slot = match node.name().value() {
+            "fg" => &mut palette.gray,
+            "black" => {
+                palette.bg = Some(color);
+                &mut palette.black
+            }
+            "red" => &mut palette.red,
+            "green" => &mut palette.green,
+            "yellow" => &mut palette.yellow,
+            "blue" => &mut palette.blue,
+            "magenta" => &mut palette.magenta,
+            "cyan" => &mut palette.cyan,
+            "white" => &mut palette.white,
+            _ => continue,
+        };
+        *slot = Some(color);
+        found = true;
+    }
+
+    match found {
+        true => Ok(palette),
+        false => Err(format!("{path}: no colors in the theme")),
+    }
+}
+
+// NOTE: palette of the running zellij theme as sent with every mode update, mapped the same way
+// as theme files
+pub fn from_zellij(colors: &ZellijPalette) -> Palette {
+    let color = |x: ZellijColor| -> Option<Color> {
+        match x {
+            ZellijColor::Rgb((r, g, b)) => Some(RgbColor(r, g, b).into()),
+            ZellijColor::EightBit(index) => Some(Ansi256Color(index).into()),
+        }
+    };
+    Palette {
+        bg: color(colors.black),
+        red: color(colors.red),
+        green: color(colors.green),
+        yellow: color(colors.yellow),
+        blue: color(colors.blue),
+        magenta: color(colors.magenta),
+        cyan: color(colors.cyan),
+        gray: color(colors.fg),
+        white: color(colors.white),
+        black: color(colors.black),
+    }
+}
+
+// INFO: plugins see the host filesystem under `/host` only, relative paths are resolved there.
+// The home directory isn't known inside the sandbox, so `~` can't be expanded.
+pub fn host_path(path: &str) -> Result<String, String> {
+    if path.starts_with('~') {
+        Err(format!(
+            "{path}: `~` isn't expanded, give the path relative to where zellij was started"
+        ))
+    } else if path.starts_with('/') {
+        Ok(path.to_string())
+    } else {
+        Ok(format!("/host/{path}"))
+    }
+}
+
+fn parse_theme_color(values: &[&str]) -> Option<Color> {
+    match values {
+        [value] => match value.parse::<u8>() {
+            Ok(index) => Some(Ansi256Color(index).into()),
+            Err(_) => parse_color(value),
+        },
+        [r, g, b] => Some(RgbColor(r.parse().ok()?, g.parse().ok()?, b.parse().ok()?).into()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loads_the_first_theme_of_a_kdl_file() {
+        let path = std::env::temp_dir().join("statusline-theme.kdl");
+        fs::write(
+            &path,
+            "themes {\n    dark { fg \"#cdd6f4\"; red 243 139 168; }\n    light {\n        \
+             blue 4 // accent\n    }\n}\n",
+        )
+        .unwrap();
+        let palette = load(path.to_str().unwrap()).unwrap();
+        assert_eq!(palette.gray, parse_color("#cdd6f4"));
+        assert_eq!(palette.red, Some(RgbColor(243, 139, 168).into()));
+        assert_eq!(palette.blue, Palette::default().blue);
+        assert!(load("~/.config/zellij/themes/dark.kdl").is_err());
+    }
+}
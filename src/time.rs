@@ -0,0 +1,21 @@
+use std::cell::Cell;
+
+use chrono::{DateTime, Local};
+
+thread_local! {
+    // NOTE: pinned instant replacing the wall clock, so clocks, expiry and animation phases
+    // render deterministically (snapshots, headless renders)
+    static FIXED: Cell<Option<DateTime<Local>>> = const { Cell::new(None) };
+}
+
+pub fn set_fixed(now: Option<DateTime<Local>>) {
+    FIXED.with(|x| x.set(now));
+}
+
+pub fn now() -> DateTime<Local> {
+    FIXED.with(|x| x.get()).unwrap_or_else(Local::now)
+}
+
+pub fn is_fixed() -> bool {
+    FIXED.with(|x| x.get()).is_some()
+}
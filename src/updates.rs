@@ -0,0 +1,54 @@
+use anstyle::Style as AnsiStyle;
+use zellij_tile::prelude::*;
+
+use crate::{glyph, poll, widget::Widget, Palette, Segment};
+
+// NOTE: pending system package updates, counted by the `updates_check` command
+#[derive(Default)]
+pub struct Updates {
+    count: Option<usize>,
+}
+
+impl Widget for Updates {
+    fn interested_events(&self) -> Vec<EventType> {
+        vec![EventType::RunCommandResult]
+    }
+
+    // INFO: either a plain number (`checkupdates | wc -l`) or one package per line
+    // (`apt list --upgradable`, whose header line is skipped)
+    fn update(&mut self, event: &Event) -> bool {
+        let Event::RunCommandResult(_, stdout, _, context) = event else {
+            return false;
+        };
+        if context.get(poll::POLL).map(String::as_str) != Some("updates") {
+            return false;
+        }
+        let stdout = String::from_utf8_lossy(stdout);
+        let count = stdout.trim().parse().unwrap_or_else(|_| {
+            stdout
+                .lines()
+                .filter(|x| !x.trim().is_empty() && !x.starts_with("Listing..."))
+                .count()
+        });
+        let changed = self.count != Some(count);
+        self.count = Some(count);
+        changed
+    }
+
+    fn is_loaded(&self) -> bool {
+        self.count.is_some()
+    }
+
+    fn render(&self, palette: &Palette) -> Segment {
+        let Some(count) = self.count.filter(|x| *x > 0) else {
+            return Segment::empty();
+        };
+        Segment::new(
+            Box::new(format!("{} {count}", glyph("󰏔", "upd"))),
+            AnsiStyle::new()
+                .fg_color(palette.black)
+                .bg_color(palette.yellow),
+            palette,
+        )
+    }
+}
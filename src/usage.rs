@@ -0,0 +1,157 @@
+use std::{cmp::Reverse, collections::BTreeMap, fs};
+
+use anstyle::Style as AnsiStyle;
+use chrono::{DateTime, Duration, Local};
+use zellij_tile::prelude::*;
+
+use crate::{glyph, human::HumanFormat, time, widget::Widget, Palette, Segment};
+
+// NOTE: seconds spent in each input mode, keyed by day - `2024-02-12\tlocked\t3600` lines
+const USAGE_PATH: &str = "/data/mode-usage";
+// NOTE: the time of the current mode is credited at least this often, so little is lost when
+// the session ends
+const FLUSH_SECONDS: i64 = 60;
+// NOTE: a week of days is kept for the weekly totals
+const KEEP_DAYS: i64 = 7;
+
+fn day(date: DateTime<Local>) -> String {
+    date.format("%Y-%m-%d").to_string()
+}
+
+fn name(mode: InputMode) -> String {
+    format!("{mode:?}").to_lowercase()
+}
+
+// NOTE: time spent in each input mode with `mode_usage`, the element shows the mode other than
+// normal used the most today and `statusline::mode-usage` dumps the totals
+#[derive(Default)]
+pub struct Usage {
+    days: BTreeMap<String, BTreeMap<String, u64>>,
+    active: Option<(String, DateTime<Local>)>,
+    pub enabled: bool,
+    pub human: HumanFormat,
+}
+
+impl Widget for Usage {
+    fn interested_events(&self) -> Vec<EventType> {
+        vec![EventType::ModeUpdate, EventType::Timer]
+    }
+
+    // INFO: returns `true` when the time of a mode was credited
+    fn update(&mut self, event: &Event) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        match event {
+            Event::ModeUpdate(info) => self.switch(&name(info.mode)),
+            Event::Timer(_) => self.flush(false),
+            _ => false,
+        }
+    }
+
+    fn render(&self, palette: &Palette) -> Segment {
+        let Some((mode, seconds)) = self
+            .totals(1)
+            .into_iter()
+            .find(|(mode, _)| mode != "normal")
+        else {
+            return Segment::empty();
+        };
+        Segment::new(
+            Box::new(format!(
+                "{} {mode} {}",
+                glyph("󰔛", "mode"),
+                self.human.duration(seconds as f64)
+            )),
+            AnsiStyle::new()
+                .fg_color(palette.black)
+                .bg_color(palette.gray),
+            palette,
+        )
+    }
+}
+
+impl Usage {
+    pub fn load() -> Self {
+        let mut usage = Self::default();
+        for line in fs::read_to_string(USAGE_PATH).unwrap_or_default().lines() {
+            let mut fields = line.split('\t');
+            let (Some(day), Some(mode), Some(Ok(seconds))) =
+                (fields.next(), fields.next(), fields.next().map(str::parse))
+            else {
+                continue;
+            };
+            usage
+                .days
+                .entry(day.to_string())
+                .or_default()
+                .insert(mode.to_string(), seconds);
+        }
+        usage
+    }
+
+    // INFO: modes sorted by the time spent in them over the last `days` days, today included
+    pub fn totals(&self, days: i64) -> Vec<(String, u64)> {
+        let now = time::now();
+        let included: Vec<String> = (0..days).map(|x| day(now - Duration::days(x))).collect();
+        let mut totals: BTreeMap<&str, u64> = BTreeMap::new();
+        for (_, modes) in self.days.iter().filter(|(x, _)| included.contains(*x)) {
+            for (mode, seconds) in modes {
+                *totals.entry(mode.as_str()).or_default() += seconds;
+            }
+        }
+        let mut totals: Vec<(String, u64)> = totals
+            .into_iter()
+            .map(|(mode, seconds)| (mode.to_string(), seconds))
+            .collect();
+        totals.sort_by_key(|x| Reverse(x.1));
+        totals
+    }
+
+    fn switch(&mut self, mode: &str) -> bool {
+        if self.active.as_ref().is_some_and(|(name, _)| name == mode) {
+            return false;
+        }
+        let credited = self.flush(true);
+        self.active = Some((mode.to_string(), time::now()));
+        credited
+    }
+
+    // INFO: credits the time since the last switch or flush to the active mode, the day it
+    // started gets all of it
+    fn flush(&mut self, force: bool) -> bool {
+        let now = time::now();
+        let Some((mode, since)) = &mut self.active else {
+            return false;
+        };
+        if !force && now - *since < Duration::seconds(FLUSH_SECONDS) {
+            return false;
+        }
+        let seconds = (now - *since).num_seconds().max(0) as u64;
+        *self
+            .days
+            .entry(day(*since))
+            .or_default()
+            .entry(mode.clone())
+            .or_default() += seconds;
+        *since = now;
+        self.save();
+        true
+    }
+
+    fn save(&mut self) {
+        let now = time::now();
+        let keep: Vec<String> = (0..KEEP_DAYS)
+            .map(|x| day(now - Duration::days(x)))
+            .collect();
+        self.days.retain(|day, _| keep.contains(day));
+
+        let mut lines = Vec::new();
+        for (day, modes) in &self.days {
+            for (mode, seconds) in modes {
+                lines.push(format!("{day}\t{mode}\t{seconds}"));
+            }
+        }
+        let _ = fs::write(USAGE_PATH, lines.join("\n"));
+    }
+}
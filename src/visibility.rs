@@ -0,0 +1,273 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Datelike, Local, Timelike};
+
+// NOTE: suffix of configuration keys like `clock_visible = "cols > 120"` or
+// `session_visible = "mode != normal && tabs > 1"`
+pub const SUFFIX: &str = "_visible";
+
+// INFO: what conditions can refer to - `mode`, `tabs`, `cols`, `hour`, `minute` and `day` (`mon`)
+pub struct Facts<'a> {
+    pub mode: &'a str,
+    pub tabs: usize,
+    pub cols: usize,
+    pub now: DateTime<Local>,
+}
+
+#[derive(PartialEq)]
+enum Token {
+    Word(String),
+    Number(f64),
+    Op(&'static str),
+}
+
+enum Operand {
+    Word(String),
+    Number(f64),
+}
+
+enum Expr {
+    Or(Box<Expr>, Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Compare(Operand, &'static str, Operand),
+}
+
+// NOTE: elements shown only while their condition holds, evaluated on every render
+#[derive(Default)]
+pub struct Visibility {
+    conditions: BTreeMap<String, Expr>,
+}
+
+impl Visibility {
+    // INFO: invalid conditions are left out, `config::parse` reports them
+    pub fn new(config: &BTreeMap<String, String>) -> Self {
+        let conditions = config
+            .iter()
+            .filter_map(|(key, value)| Some((key.strip_suffix(SUFFIX)?, parse(value).ok()?)))
+            .map(|(name, condition)| (name.to_string(), condition))
+            .collect();
+        Self { conditions }
+    }
+
+    pub fn is_visible(&self, name: &str, facts: &Facts) -> bool {
+        self.conditions.get(name).is_none_or(|x| x.evaluate(facts))
+    }
+}
+
+pub fn validate(condition: &str) -> Result<(), String> {
+    parse(condition).map(|_| ())
+}
+
+impl Expr {
+    fn evaluate(&self, facts: &Facts) -> bool {
+        match self {
+            Expr::Or(left, right) => left.evaluate(facts) || right.evaluate(facts),
+            Expr::And(left, right) => left.evaluate(facts) && right.evaluate(facts),
+            Expr::Not(expr) => !expr.evaluate(facts),
+            Expr::Compare(left, op, right) => {
+                let ordering = match (left.resolve(facts), right.resolve(facts)) {
+                    (Operand::Number(x), Operand::Number(y)) => x.partial_cmp(&y),
+                    (x, y) => Some(x.to_string().cmp(&y.to_string())),
+                };
+                let Some(ordering) = ordering else {
+                    return false;
+                };
+                match *op {
+                    "==" => ordering.is_eq(),
+                    "!=" => ordering.is_ne(),
+                    "<" => ordering.is_lt(),
+                    "<=" => ordering.is_le(),
+                    ">" => ordering.is_gt(),
+                    _ => ordering.is_ge(),
+                }
+            }
+        }
+    }
+}
+
+impl Operand {
+    // INFO: words naming a fact are replaced by it, the rest are plain text - `normal`, `locked`
+    fn resolve(&self, facts: &Facts) -> Operand {
+        let word = match self {
+            Operand::Number(x) => return Operand::Number(*x),
+            Operand::Word(word) => word,
+        };
+        match word.as_str() {
+            "mode" => Operand::Word(facts.mode.to_string()),
+            "tabs" => Operand::Number(facts.tabs as f64),
+            "cols" => Operand::Number(facts.cols as f64),
+            "hour" => Operand::Number(facts.now.hour() as f64),
+            "minute" => Operand::Number(facts.now.minute() as f64),
+            "day" => Operand::Word(facts.now.weekday().to_string().to_lowercase()),
+            word => Operand::Word(word.to_string()),
+        }
+    }
+}
+
+impl std::fmt::Display for Operand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Operand::Word(x) => write!(f, "{x}"),
+            Operand::Number(x) => write!(f, "{x}"),
+        }
+    }
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>, String> {
+    const OPS: [&str; 12] = [
+        "==", "!=", "<=", ">=", "&&", "||", "<", ">", "!", "(", ")", "=",
+    ];
+    let mut tokens = Vec::new();
+    let mut rest = text.trim_start();
+    while !rest.is_empty() {
+        if let Some(op) = OPS.iter().find(|x| rest.starts_with(*x)) {
+            // INFO: a single `=` reads as `==`
+            tokens.push(Token::Op(if *op == "=" { "==" } else { op }));
+            rest = &rest[op.len()..];
+        } else if let Some(quoted) = rest.strip_prefix(['"', '\'']) {
+            let quote = rest.chars().next().unwrap_or('"');
+            let end = quoted.find(quote).ok_or("unterminated quote")?;
+            tokens.push(Token::Word(quoted[..end].to_string()));
+            rest = &quoted[end + 1..];
+        } else {
+            let end = rest
+                .find(|x: char| x.is_whitespace() || "=!<>&|()\"'".contains(x))
+                .unwrap_or(rest.len());
+            if end == 0 {
+                return Err(format!("unexpected `{}`", &rest[..1]));
+            }
+            let word = &rest[..end];
+            tokens.push(match word {
+                "and" => Token::Op("&&"),
+                "or" => Token::Op("||"),
+                "not" => Token::Op("!"),
+                word => match word.parse() {
+                    Ok(number) => Token::Number(number),
+                    Err(_) => Token::Word(word.to_lowercase()),
+                },
+            });
+            rest = &rest[end..];
+        }
+        rest = rest.trim_start();
+    }
+    Ok(tokens)
+}
+
+// NOTE: `||` binds looser than `&&`, `!` and parentheses bind tightest
+fn parse(text: &str) -> Result<Expr, String> {
+    let tokens = tokenize(text)?;
+    let mut position = 0;
+    let expr = or(&tokens, &mut position)?;
+    match tokens.get(position) {
+        None => Ok(expr),
+        Some(_) => Err("unexpected input after the condition".to_string()),
+    }
+}
+
+fn or(tokens: &[Token], position: &mut usize) -> Result<Expr, String> {
+    let mut expr = and(tokens, position)?;
+    while tokens.get(*position) == Some(&Token::Op("||")) {
+        *position += 1;
+        expr = Expr::Or(Box::new(expr), Box::new(and(tokens, position)?));
+    }
+    Ok(expr)
+}
+
+fn and(tokens: &[Token], position: &mut usize) -> Result<Expr, String> {
+    let mut expr = unary(tokens, position)?;
+    while tokens.get(*position) == Some(&Token::Op("&&")) {
+        *position += 1;
+        expr = Expr::And(Box::new(expr), Box::new(unary(tokens, position)?));
+    }
+    Ok(expr)
+}
+
+fn unary(tokens: &[Token], position: &mut usize) -> Result<Expr, String> {
+    match tokens.get(*position) {
+        Some(Token::Op("!")) => {
+            *position += 1;
+            Ok(Expr::Not(Box::new(unary(tokens, position)?)))
+        }
+        Some(Token::Op("(")) => {
+            *position += 1;
+            let expr = or(tokens, position)?;
+            if tokens.get(*position) != Some(&Token::Op(")")) {
+                return Err("missing `)`".to_string());
+            }
+            *position += 1;
+            Ok(expr)
+        }
+        _ => {
+            let left = operand(tokens, position)?;
+            let op = match tokens.get(*position) {
+                Some(Token::Op(op @ ("==" | "!=" | "<" | "<=" | ">" | ">="))) => *op,
+                _ => {
+                    return Err(
+                        "expected a comparison - `==`, `!=`, `<`, `<=`, `>` or `>=`".to_string()
+                    )
+                }
+            };
+            *position += 1;
+            let right = operand(tokens, position)?;
+            Ok(Expr::Compare(left, op, right))
+        }
+    }
+}
+
+fn operand(tokens: &[Token], position: &mut usize) -> Result<Operand, String> {
+    let operand = match tokens.get(*position) {
+        Some(Token::Word(word)) => Operand::Word(word.clone()),
+        Some(Token::Number(number)) => Operand::Number(*number),
+        Some(Token::Op(op)) => return Err(format!("unexpected `{op}`")),
+        None => return Err("unexpected end of the condition".to_string()),
+    };
+    *position += 1;
+    Ok(operand)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn holds(condition: &str) -> bool {
+        let facts = Facts {
+            mode: "normal",
+            tabs: 3,
+            cols: 100,
+            now: Local.with_ymd_and_hms(2024, 1, 1, 9, 30, 0).unwrap(),
+        };
+        parse(condition).unwrap().evaluate(&facts)
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        assert!(holds("tabs > 5 && cols > 50 || mode == normal"));
+        assert!(!holds("tabs > 5 && (cols > 50 || mode == normal)"));
+        assert!(holds("!tabs > 5 && cols > 50"));
+        assert!(!holds("!(tabs < 5 && cols > 50)"));
+    }
+
+    #[test]
+    fn reads_words_and_single_equals() {
+        assert!(holds("mode = normal"));
+        assert!(holds("mode = 'normal' and day == \"mon\""));
+        assert!(holds("not hour < 9 or minute == 0"));
+        assert!(!holds("tabs == 3 and not cols >= 100"));
+    }
+
+    #[test]
+    fn reports_invalid_conditions() {
+        assert_eq!(validate("(tabs > 1").unwrap_err(), "missing `)`");
+        assert_eq!(
+            validate("tabs > 1 cols").unwrap_err(),
+            "unexpected input after the condition"
+        );
+        assert_eq!(
+            validate("mode == 'normal").unwrap_err(),
+            "unterminated quote"
+        );
+    }
+}
@@ -0,0 +1,33 @@
+use zellij_tile::prelude::*;
+
+use crate::{Palette, Segment};
+
+// NOTE: element of the bar keeping its own state up to date from the events it's interested in,
+// `PluginState::update` dispatches events to every registered widget
+pub trait Widget {
+    fn interested_events(&self) -> Vec<EventType> {
+        vec![]
+    }
+
+    // INFO: returns `true` when the widget has to be rendered again
+    fn update(&mut self, _event: &Event) -> bool {
+        false
+    }
+
+    fn render(&self, palette: &Palette) -> Segment;
+
+    // INFO: hash of the inputs the segment depends on - mode, tabs, time bucket - so `Memo` can
+    // reuse it, `None` renders it every time
+    fn state(&self) -> Option<u64> {
+        None
+    }
+
+    // INFO: `false` until the first result of a polled command or request arrived
+    fn is_loaded(&self) -> bool {
+        true
+    }
+}
+
+pub fn dispatch(widget: &mut dyn Widget, event: &Event) -> bool {
+    widget.interested_events().contains(&EventType::from(event)) && widget.update(event)
+}
@@ -0,0 +1,88 @@
+use std::{collections::BTreeMap, fmt::Display, fs, path::Path};
+
+use crate::{config, i18n};
+
+// NOTE: marker written once the wizard was completed or skipped, so it is shown on the first run only
+const DONE_MARKER: &str = "/data/wizard-done";
+
+const SEPARATORS: &[&str] = &["angled", "round", "flame", "none"];
+const CLOCK_FORMATS: &[&str] = &["%H:%M", "%H:%M:%S", "%Y-%m-%d %H:%M", "%a %d %b %H:%M"];
+const TIMEZONES: &[&str] = &[
+    "UTC",
+    "Europe/London",
+    "Europe/Warsaw",
+    "America/New_York",
+    "America/Los_Angeles",
+    "Asia/Tokyo",
+];
+
+pub struct Wizard {
+    steps: Vec<(&'static str, Vec<&'static str>)>,
+    step: usize,
+    choices: Vec<usize>,
+}
+
+impl Default for Wizard {
+    fn default() -> Self {
+        let steps = vec![
+            ("preset", config::presets().collect()),
+            ("separator", SEPARATORS.to_vec()),
+            ("clock_format", CLOCK_FORMATS.to_vec()),
+            ("timezone", TIMEZONES.to_vec()),
+        ];
+        Self {
+            choices: vec![0; steps.len()],
+            steps,
+            step: 0,
+        }
+    }
+}
+
+impl Display for Wizard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (key, options) = &self.steps[self.step];
+        write!(
+            f,
+            "{} {}/{}  {key}: < {} >  {}",
+            i18n::tr("setup"),
+            self.step + 1,
+            self.steps.len(),
+            options[self.choices[self.step]],
+            i18n::tr("setup_hint"),
+        )
+    }
+}
+
+impl Wizard {
+    pub fn should_run(config: &BTreeMap<String, String>) -> bool {
+        config.is_empty() && !Path::new(DONE_MARKER).exists()
+    }
+
+    pub fn next_option(&mut self) {
+        let count = self.steps[self.step].1.len();
+        self.choices[self.step] = (self.choices[self.step] + 1) % count;
+    }
+
+    pub fn previous_option(&mut self) {
+        let count = self.steps[self.step].1.len();
+        self.choices[self.step] = (self.choices[self.step] + count - 1) % count;
+    }
+
+    // INFO: returns `true` once the last step was confirmed
+    pub fn next_step(&mut self) -> bool {
+        self.step += 1;
+        self.step == self.steps.len()
+    }
+
+    pub fn config(&self) -> BTreeMap<String, String> {
+        self.steps
+            .iter()
+            .zip(&self.choices)
+            .map(|((key, options), choice)| (key.to_string(), options[*choice].to_string()))
+            .collect()
+    }
+
+    pub fn finish() {
+        let _ = fs::write(DONE_MARKER, "");
+    }
+}
@@ -0,0 +1,60 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use zellij_tile::prelude::*;
+
+use crate::{git, sanitize};
+
+// NOTE: `statusline_worker` registered in `main.rs`, zellij drops the `_worker` suffix
+pub const NAME: &str = "statusline";
+
+// NOTE: jobs, also the names of the messages posted back with their results
+pub const HTTP: &str = "http";
+pub const GIT_WORKTREES: &str = "git_worktrees";
+
+// NOTE: parses command output and response bodies off the plugin thread, results come back as
+// `CustomMessage` events
+#[derive(Default, Serialize, Deserialize)]
+pub struct Worker {}
+
+#[derive(Serialize, Deserialize)]
+pub struct HttpJob {
+    pub path: String,
+    pub body: String,
+}
+
+impl<'de> ZellijWorker<'de> for Worker {
+    fn on_message(&mut self, message: String, payload: String) {
+        let result = match message.as_str() {
+            // INFO: empty result for bodies without the value
+            HTTP => serde_json::from_str::<HttpJob>(&payload)
+                .ok()
+                .and_then(|job| extract(&job.path, &job.body))
+                .unwrap_or_default(),
+            GIT_WORKTREES => {
+                serde_json::to_string(&git::worktrees(payload.as_bytes())).unwrap_or_default()
+            }
+            _ => return,
+        };
+        post_message_to_plugin(PluginMessage::new_to_plugin(&message, &result));
+    }
+}
+
+pub fn post(job: &str, payload: &str) {
+    post_message_to(PluginMessage::new_to_worker(NAME, job, payload));
+}
+
+// INFO: dotted paths map onto JSON pointers, array items are addressed by index - `.items.0.name`
+fn extract(path: &str, body: &str) -> Option<String> {
+    let value = serde_json::from_str::<Value>(body).ok()?;
+    let pointer = path.trim_start_matches('.').replace('.', "/");
+    let value = if pointer.is_empty() {
+        &value
+    } else {
+        value.pointer(&format!("/{pointer}"))?
+    };
+    match value {
+        Value::String(x) => Some(sanitize::text(x)),
+        Value::Null => None,
+        x => Some(x.to_string()),
+    }
+}
@@ -0,0 +1,72 @@
+use std::collections::BTreeMap;
+
+use anstyle::{Color, Style as AnsiStyle};
+use zellij_tile::prelude::*;
+
+use crate::{parse_color, widget::Widget, Palette, Segment};
+
+// NOTE: zellij doesn't expose the name of the layout a session was started with - the plugin
+// configuration lives inside that layout though, so `workspace` can be set per layout file.
+// Without it the swap layout name of the active tab is shown.
+#[derive(Default)]
+pub struct Workspace {
+    name: Option<String>,
+    configured: bool,
+    colors: BTreeMap<String, Color>,
+}
+
+impl Widget for Workspace {
+    fn interested_events(&self) -> Vec<EventType> {
+        vec![EventType::TabUpdate]
+    }
+
+    // INFO: follows the swap layout of the active tab unless configured
+    fn update(&mut self, event: &Event) -> bool {
+        let Event::TabUpdate(tabs) = event else {
+            return false;
+        };
+        let swap_layout = tabs
+            .iter()
+            .find(|x| x.active)
+            .and_then(|x| x.active_swap_layout_name.clone());
+        if self.configured || self.name == swap_layout {
+            return false;
+        }
+        self.name = swap_layout;
+        true
+    }
+
+    fn render(&self, palette: &Palette) -> Segment {
+        let Some(name) = &self.name else {
+            return Segment::empty();
+        };
+        let bg = self.colors.get(name).copied().or(palette.blue);
+        Segment::new(
+            Box::new(name.clone()),
+            AnsiStyle::new().fg_color(palette.black).bg_color(bg),
+            palette,
+        )
+    }
+}
+
+impl Workspace {
+    // INFO: `colors` maps layout names to colors - `api:blue,web:#a6e3a1`, invalid colors are
+    // left out for the default one and reported by `config::parse`
+    pub fn new(name: Option<&String>, colors: Option<&String>) -> Self {
+        let colors = colors
+            .map(|x| {
+                x.split(',')
+                    .filter_map(|x| x.split_once(':'))
+                    .filter_map(|(name, color)| {
+                        Some((name.trim().to_string(), parse_color(color)?))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self {
+            name: name.cloned(),
+            configured: name.is_some(),
+            colors,
+        }
+    }
+}